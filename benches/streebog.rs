@@ -0,0 +1,60 @@
+// Throughput benchmarks (run with `cargo bench`, add `--features low-level` to include single
+// compression). Criterion reports throughput in MiB/s and compares with results of previous run.
+
+#[macro_use]
+extern crate criterion;
+extern crate streebog_hash;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use streebog_hash::{hash512, StreebogHasher, StreebogHasher512};
+
+#[cfg(feature = "low-level")]
+fn bench_compression(c: &mut Criterion) {
+    let block = [[0x5au8; 64]];
+    let mut group = c.benchmark_group("g_N");
+    group.throughput(Throughput::Bytes(64));
+    group.bench_function("one block", |b| {
+        b.iter(|| streebog_hash::compress_only(criterion::black_box(&block)))
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "low-level"))]
+fn bench_compression(_: &mut Criterion) {}
+
+fn bench_hash512(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash512");
+    for size in [64usize, 1 << 10, 1 << 20, 64 << 20].iter() {
+        let data = vec![0x5au8; *size];
+        if *size >= 1 << 20 {
+            group.sample_size(10);
+        }
+        group.throughput(Throughput::Bytes(*size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &data, |b, data| {
+            b.iter(|| hash512(data))
+        });
+    }
+    group.finish();
+}
+
+fn bench_small_updates(c: &mut Criterion) {
+    let data = vec![0x5au8; 1 << 20];
+    let mut group = c.benchmark_group("update 1 MiB by chunks");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.sample_size(10);
+    for chunk in [1usize, 16, 100].iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(chunk), chunk, |b, chunk| {
+            b.iter(|| {
+                let mut hasher = StreebogHasher512::new();
+                for part in data.chunks(*chunk) {
+                    hasher.update(part);
+                }
+                hasher.finalize()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_compression, bench_hash512, bench_small_updates);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+//! Input: number of split offsets `n` (first byte, modulo 16), then `n` offsets (little-endian
+//! `u16`, taken modulo data length), then data. Data is fed to `update` in pieces and digest is
+//! compared with one-shot hash.
+//!
+//! Run with `cargo +nightly fuzz run chunked_update` (seed corpus is built from test vectors).
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use streebog_hash::{hash256, hash512, StreebogHasher, StreebogHasher256, StreebogHasher512};
+
+fn split(input: &[u8]) -> (Vec<usize>, &[u8]) {
+    let (count, rest) = match input.split_first() {
+        Some((count, rest)) => ((*count % 16) as usize, rest),
+        None => return (Vec::new(), input),
+    };
+    let count = count.min(rest.len() / 2);
+    let (offsets, data) = rest.split_at(count * 2);
+    let mut offsets: Vec<usize> = offsets
+        .chunks(2)
+        .map(|i| u16::from_le_bytes([i[0], i[1]]) as usize % (data.len() + 1))
+        .collect();
+    offsets.sort_unstable();
+    (offsets, data)
+}
+
+fn feed<H: StreebogHasher>(hasher: &mut H, offsets: &[usize], data: &[u8]) {
+    let mut start = 0;
+    for &offset in offsets {
+        hasher.update(&data[start..offset]);
+        start = offset;
+    }
+    hasher.update(&data[start..]);
+}
+
+fuzz_target!(|input: &[u8]| {
+    let (offsets, data) = split(input);
+
+    let mut hasher = StreebogHasher512::new();
+    feed(&mut hasher, &offsets, data);
+    assert_eq!(hasher.finalize()[..], hash512(data)[..], "offsets: {:?}", offsets);
+
+    let mut hasher = StreebogHasher256::new();
+    feed(&mut hasher, &offsets, data);
+    assert_eq!(hasher.finalize()[..], hash256(data)[..], "offsets: {:?}", offsets);
+});
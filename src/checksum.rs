@@ -0,0 +1,112 @@
+use std::fs::File;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+
+use {ct_eq, parse_hex, StreebogError, StreebogHasher, StreebogHasher256, StreebogHasher512};
+
+/// Verifies files listed in checksum file.
+///
+/// Every line of checksum file must have form `<hex>  <filename>` (digest, two spaces and path
+/// relative to `base_dir`), where digest is in big-endian byte order (as returned by
+/// `get_result_str`, but without `0x` prefix) in any case. Digest size is determined by its
+/// length: 64 hex digits for Streebog-256 and 128 for Streebog-512. Empty lines are skipped.
+///
+/// Returns path (joined with `base_dir`) and result of comparison for every file in order of
+/// lines. Malformed line (not `<hex>  <filename>`) gives `StreebogError::MalformedLine` with its
+/// number, and failure to read checksum file or any listed file gives `StreebogError::Io`.
+pub fn verify_checksum_file<R: BufRead>(lines: R, base_dir: &Path)
+                                        -> Result<Vec<(PathBuf, bool)>, StreebogError> {
+    let mut result = Vec::new();
+    for (i, line) in lines.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            continue;
+        }
+        let (expected, file_name) = match parse_line(line) {
+            Some(parsed) => parsed,
+            None => return Err(StreebogError::MalformedLine { line: i + 1 }),
+        };
+        let path = base_dir.join(file_name);
+        let mut file = File::open(&path)?;
+        let actual = if expected.len() == 64 {
+            let mut hasher = StreebogHasher512::new();
+            hasher.update_from_reader(&mut file)?;
+            hasher.finish();
+            hasher.get_result()
+        } else {
+            let mut hasher = StreebogHasher256::new();
+            hasher.update_from_reader(&mut file)?;
+            hasher.finish();
+            hasher.get_result()
+        };
+        result.push((path, ct_eq(&actual, &expected)));
+    }
+    Ok(result)
+}
+
+fn parse_line(line: &str) -> Option<(Vec<u8>, &str)> {
+    let separator = line.find("  ")?;
+    let file_name = &line[separator + 2..];
+    let digest = parse_hex(&line[..separator])?;
+    if file_name.is_empty() || (digest.len() != 32 && digest.len() != 64) {
+        return None;
+    }
+    Some((digest, file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io;
+    use std::string::String;
+    use {hash256, hash512, hex_bytes};
+
+    fn hex(data: &[u8]) -> String {
+        String::from_utf8(hex_bytes(data, false)).unwrap()
+    }
+
+    #[test]
+    fn test_verify_checksum_file() {
+        let dir = ::std::env::temp_dir()
+            .join(format!("streebog_hash_test_checksum_{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), b"first file").unwrap();
+        fs::write(dir.join("b c.bin"), [0xfcu8; 100]).unwrap();
+
+        let checksums = format!("{}  a.txt\n\n{}  b c.bin\r\n{}  a.txt\n",
+                                hex(&hash512(b"first file")),
+                                hex(&hash256(&[0xfcu8; 100])).to_uppercase(),
+                                hex(&hash256(b"other content")));
+        let result = verify_checksum_file(checksums.as_bytes(), &dir);
+
+        let malformed = verify_checksum_file(&b"0011  a.txt\n"[..], &dir);
+        let missing = format!("{}  missing.txt\n", hex(&hash512(b"")));
+        let missing = verify_checksum_file(missing.as_bytes(), &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(result.unwrap(),
+                   vec![(dir.join("a.txt"), true), (dir.join("b c.bin"), true),
+                        (dir.join("a.txt"), false)]);
+        match malformed.unwrap_err() {
+            StreebogError::MalformedLine { line } => assert_eq!(line, 1),
+            err => panic!("unexpected error: {}", err),
+        }
+        match missing.unwrap_err() {
+            StreebogError::Io(err) => assert_eq!(err.kind(), io::ErrorKind::NotFound),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let digest = hex(&[0xabu8; 32]);
+        assert_eq!(parse_line(&format!("{}  file", digest)), Some((vec![0xabu8; 32], "file")));
+        assert_eq!(parse_line(&format!("{} file", digest)), None);
+        assert_eq!(parse_line(&format!("{}  ", digest)), None);
+        assert_eq!(parse_line("xyz  file"), None);
+        assert_eq!(parse_line(&format!("{}ab  file", digest)), None);
+    }
+}
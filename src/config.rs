@@ -0,0 +1,128 @@
+use alloc::string::String;
+
+use {hex_bytes, StreebogHasher, StreebogHasher256, StreebogHasher512};
+
+/// Configuration of digest size and formatting of result in one place (e.g. for tools, which
+/// expose these options to user).
+///
+/// # Examples
+///
+/// ```
+/// use streebog_hash::StreebogConfig;
+/// let config = StreebogConfig::new().width(256).uppercase(true).prefix(false);
+/// println!("{}", config.hash(&[0xfcu8; 128]));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreebogConfig {
+    width: u32,
+    uppercase: bool,
+    prefix: bool,
+    truncate: Option<usize>,
+}
+
+impl StreebogConfig {
+    /// Creates configuration with default options: 512 bit digest, lowercase hex with `0x` prefix
+    /// and without truncation (i.e. the same as `get_result_str`).
+    pub fn new() -> StreebogConfig {
+        StreebogConfig {
+            width: 512,
+            uppercase: false,
+            prefix: true,
+            truncate: None,
+        }
+    }
+
+    /// Sets digest size in bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if width is not 256 or 512.
+    pub fn width(mut self, width: u32) -> StreebogConfig {
+        assert!(width == 256 || width == 512,
+                "Streebog digest width must be 256 or 512, got {}",
+                width);
+        self.width = width;
+        self
+    }
+
+    /// Sets case of hex digits.
+    pub fn uppercase(mut self, uppercase: bool) -> StreebogConfig {
+        self.uppercase = uppercase;
+        self
+    }
+
+    /// Sets whether result starts with `0x`.
+    pub fn prefix(mut self, prefix: bool) -> StreebogConfig {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Sets number of leading (most significant) bytes of digest to keep (`None` keeps whole
+    /// digest).
+    pub fn truncate(mut self, truncate: Option<usize>) -> StreebogConfig {
+        self.truncate = truncate;
+        self
+    }
+
+    /// Hashes data and returns formatted result.
+    pub fn hash(&self, data: &[u8]) -> String {
+        let digest = if self.width == 256 {
+            let mut hasher = StreebogHasher256::new();
+            hasher.update(data);
+            hasher.finish();
+            hasher.get_result()
+        } else {
+            let mut hasher = StreebogHasher512::new();
+            hasher.update(data);
+            hasher.finish();
+            hasher.get_result()
+        };
+        let len = match self.truncate {
+            Some(truncate) if truncate < digest.len() => truncate,
+            _ => digest.len(),
+        };
+        let mut result = if self.prefix {
+            String::from("0x")
+        } else {
+            String::new()
+        };
+        let hex = hex_bytes(&digest[..len], self.uppercase);
+        result.push_str(::core::str::from_utf8(&hex).expect("hex is always ASCII"));
+        result
+    }
+}
+
+impl Default for StreebogConfig {
+    fn default() -> StreebogConfig {
+        StreebogConfig::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streebog_config() {
+        let data = [0xfcu8; 128];
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(&data);
+        hasher.finish();
+        assert_eq!(StreebogConfig::new().hash(&data), hasher.get_result_str());
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(&data);
+        hasher.finish();
+        let config = StreebogConfig::new().width(256).uppercase(true).prefix(false);
+        assert_eq!(config.hash(&data), hasher.get_result_str()[2..].to_uppercase());
+        assert_eq!(config.truncate(Some(4)).hash(&data),
+                   hasher.get_result_str()[2..10].to_uppercase());
+        assert_eq!(config.truncate(Some(100)).hash(&data), config.hash(&data));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_streebog_config_invalid_width() {
+        StreebogConfig::new().width(384);
+    }
+}
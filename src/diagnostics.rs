@@ -0,0 +1,27 @@
+//! Debugging aids, which are not intended for use in production code.
+
+/// Returns number of differing bits of two digests (or any byte strings) or `None`, if lengths
+/// differ, e.g. to tell in failing test, whether wrong digest is caused by small wiring bug (few
+/// bits differ) or is garbage (about half of bits differ).
+///
+/// **Note!** It is not constant-time and must never be used to verify digests.
+pub fn hamming_distance(a: &[u8], b: &[u8]) -> Option<u32> {
+    if a.len() != b.len() {
+        return None;
+    }
+    Some(a.iter().zip(b.iter()).map(|(x, y)| (x ^ y).count_ones()).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(hamming_distance(&[], &[]), Some(0));
+        assert_eq!(hamming_distance(&[0xff, 0x00], &[0xff, 0x00]), Some(0));
+        assert_eq!(hamming_distance(&[0xff, 0x01], &[0x00, 0x03]), Some(9));
+        assert_eq!(hamming_distance(&[0u8; 32], &[0xffu8; 32]), Some(256));
+        assert_eq!(hamming_distance(&[0u8; 32], &[0u8; 64]), None);
+    }
+}
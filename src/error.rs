@@ -0,0 +1,98 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io;
+
+/// An error returned by fallible operations of this crate.
+#[derive(Debug)]
+pub enum StreebogError {
+    /// Input has unexpected length.
+    InvalidLength {
+        /// Expected length.
+        expected: usize,
+        /// Actual length.
+        actual: usize,
+    },
+    /// Hasher (or its serialized state) is not in a state, which allows requested operation.
+    InvalidState,
+    /// Serialized data has unsupported format version.
+    UnsupportedVersion,
+    /// Hasher is already finished, so data can't be written into it (or it can't be finished
+    /// again) until reset.
+    AlreadyFinished,
+    /// Total length of input exceeds limit.
+    LengthExceeded {
+        /// Maximum allowed length in bytes.
+        max: u64,
+    },
+    /// Line of text input (e.g. checksum file) is malformed.
+    MalformedLine {
+        /// Number of line, starting from 1.
+        line: usize,
+    },
+    /// Underlying I/O error.
+    #[cfg(feature = "std")]
+    Io(io::Error),
+}
+
+impl fmt::Display for StreebogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StreebogError::InvalidLength { expected, actual } => {
+                write!(f, "invalid length: expected {}, got {}", expected, actual)
+            }
+            StreebogError::InvalidState => write!(f, "invalid hasher state"),
+            StreebogError::UnsupportedVersion => write!(f, "unsupported version"),
+            StreebogError::AlreadyFinished => write!(f, "hasher is already finished"),
+            StreebogError::LengthExceeded { max } => {
+                write!(f, "input length exceeds limit of {} bytes", max)
+            }
+            StreebogError::MalformedLine { line } => write!(f, "malformed line {}", line),
+            #[cfg(feature = "std")]
+            StreebogError::Io(ref err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+/// Error of fallible hasher operations (`try_update`, `try_finish`), e.g.
+/// `HasherError::AlreadyFinished`. It is the same type as `StreebogError`.
+pub type HasherError = StreebogError;
+
+#[cfg(feature = "std")]
+impl Error for StreebogError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            StreebogError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for StreebogError {
+    fn from(err: io::Error) -> StreebogError {
+        StreebogError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_streebog_error() {
+        let err = StreebogError::InvalidLength {
+            expected: 64,
+            actual: 32,
+        };
+        assert_eq!(err.to_string(), "invalid length: expected 64, got 32");
+        assert!(err.source().is_none());
+        assert_eq!(StreebogError::MalformedLine { line: 3 }.to_string(), "malformed line 3");
+
+        let err = StreebogError::from(io::Error::other("oops"));
+        assert_eq!(err.to_string(), "I/O error: oops");
+        assert!(err.source().is_some());
+    }
+}
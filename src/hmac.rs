@@ -0,0 +1,149 @@
+//! HMAC_GOSTR3411_2012_512 and HMAC_GOSTR3411_2012_256 (RFC 2104 with Streebog, as specified by
+//! R 50.1.113-2016 and RFC 7836).
+//!
+//! **Note!** Results are in byte-string order (as in RFC 7836 test vectors), which is reversed
+//! comparing to `get_result`. Compare MACs with `ct_eq`.
+
+use {hash256, hash512, StreebogHasher, StreebogHasher256, StreebogHasher512, StreebogParams};
+
+// The same for both variants
+const BLOCK_SIZE: usize = StreebogHasher512::BLOCK_SIZE;
+
+// Key padded (or reduced to `digest`) to block size
+fn block_key(key: &[u8], digest: fn(&[u8]) -> [u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+    let mut result = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        result = digest(key);
+    } else {
+        result[..key.len()].copy_from_slice(key);
+    }
+    result
+}
+
+fn xor_pad(block_key: &[u8; BLOCK_SIZE], pad: u8) -> [u8; BLOCK_SIZE] {
+    let mut result = [pad; BLOCK_SIZE];
+    for i in result.iter_mut().zip(block_key.iter()) {
+        *i.0 ^= *i.1;
+    }
+    result
+}
+
+// Streebog-512 of key in byte-string order
+fn reduce_key512(key: &[u8]) -> [u8; BLOCK_SIZE] {
+    let mut result = hash512(key);
+    result.reverse();
+    result
+}
+
+// Streebog-256 of key in byte-string order, padded with zeroes to block size
+fn reduce_key256(key: &[u8]) -> [u8; BLOCK_SIZE] {
+    let mut result = [0u8; BLOCK_SIZE];
+    result[..32].copy_from_slice(&hash256(key));
+    result[..32].reverse();
+    result
+}
+
+macro_rules! impl_hmac {
+    ($name:ident, $hasher:ident, $size:expr, $reduce_key:ident, $doc:expr) => {
+        #[doc = $doc]
+        #[derive(Clone)]
+        pub struct $name {
+            inner: $hasher,
+            outer: $hasher,
+        }
+
+        impl $name {
+            /// Creates MAC with key of arbitrary length (key longer than block size of 64 bytes
+            /// is replaced by its digest).
+            pub fn new(key: &[u8]) -> $name {
+                let block_key = block_key(key, $reduce_key);
+                let mut inner = $hasher::new();
+                inner.update(&xor_pad(&block_key, 0x36));
+                let mut outer = $hasher::new();
+                outer.update(&xor_pad(&block_key, 0x5c));
+                $name { inner, outer }
+            }
+
+            /// Updates MAC with data. Can be called multiple times.
+            pub fn update(&mut self, data: &[u8]) {
+                self.inner.update(data);
+            }
+
+            /// Finishes computation and returns MAC in byte-string order.
+            pub fn finalize(mut self) -> [u8; $size] {
+                self.inner.finish();
+                let mut inner = self.inner.result;
+                inner.reverse();
+                self.outer.update(&inner);
+                self.outer.finish();
+                let mut result = self.outer.result;
+                result.reverse();
+                result
+            }
+        }
+    };
+}
+
+impl_hmac!(Hmac512, StreebogHasher512, 64, reduce_key512,
+           "HMAC_GOSTR3411_2012_512: HMAC with Streebog-512, 64-byte MAC.");
+impl_hmac!(Hmac256, StreebogHasher256, 32, reduce_key256,
+           "HMAC_GOSTR3411_2012_256: HMAC with Streebog-256, 32-byte MAC.");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors from R 50.1.113-2016 (RFC 7836, section 4.1)
+    static K: [u8; 32] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b,
+                          0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+                          0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f];
+    static T: [u8; 16] = [0x01, 0x26, 0xbd, 0xb8, 0x78, 0x00, 0xaf, 0x21, 0x43, 0x41, 0x45, 0x65,
+                          0x63, 0x78, 0x01, 0x00];
+
+    #[test]
+    fn test_hmac512() {
+        let should_be = [0xa5, 0x9b, 0xab, 0x22, 0xec, 0xae, 0x19, 0xc6, 0x5f, 0xbd, 0xe6, 0xe5,
+                         0xf4, 0xe9, 0xf5, 0xd8, 0x54, 0x9d, 0x31, 0xf0, 0x37, 0xf9, 0xdf, 0x9b,
+                         0x90, 0x55, 0x00, 0xe1, 0x71, 0x92, 0x3a, 0x77, 0x3d, 0x5f, 0x15, 0x30,
+                         0xf2, 0xed, 0x7e, 0x96, 0x4c, 0xb2, 0xee, 0xdc, 0x29, 0xe9, 0xad, 0x2f,
+                         0x3a, 0xfe, 0x93, 0xb2, 0x81, 0x4f, 0x79, 0xf5, 0x00, 0x0f, 0xfc, 0x03,
+                         0x66, 0xc2, 0x51, 0xe6];
+        let mut mac = Hmac512::new(&K);
+        mac.update(&T[..5]);
+        mac.update(&T[5..]);
+        assert_eq!(mac.finalize()[..], should_be[..]);
+
+        // Long key is replaced by its digest
+        let long_key = [0xaau8; 100];
+        let mut mac = Hmac512::new(&long_key);
+        mac.update(&T);
+        let mut reduced = Hmac512::new(&reduce_key512(&long_key));
+        reduced.update(&T);
+        assert_eq!(mac.finalize()[..], reduced.finalize()[..]);
+    }
+
+    #[test]
+    fn test_hmac256() {
+        let should_be = [0xa1, 0xaa, 0x5f, 0x7d, 0xe4, 0x02, 0xd7, 0xb3, 0xd3, 0x23, 0xf2, 0x99,
+                         0x1c, 0x8d, 0x45, 0x34, 0x01, 0x31, 0x37, 0x01, 0x0a, 0x83, 0x75, 0x4f,
+                         0xd0, 0xaf, 0x6d, 0x7c, 0xd4, 0x92, 0x2e, 0xd9];
+        let mut mac = Hmac256::new(&K);
+        mac.update(&T);
+        assert_eq!(mac.clone().finalize(), should_be);
+        mac.update(b"more");
+        assert_ne!(mac.finalize(), should_be);
+
+        // Key of exactly block size is used as is, longer key is replaced by its digest
+        let key = [0x01u8; 65];
+        let mut mac = Hmac256::new(&key[..64]);
+        mac.update(&T);
+        let mut reduced = Hmac256::new(&reduce_key256(&key[..64]));
+        reduced.update(&T);
+        assert_ne!(mac.finalize(), reduced.finalize());
+        let mut mac = Hmac256::new(&key);
+        mac.update(&T);
+        let mut reduced = Hmac256::new(&reduce_key256(&key));
+        reduced.update(&T);
+        assert_eq!(mac.finalize(), reduced.finalize());
+    }
+}
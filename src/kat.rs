@@ -0,0 +1,91 @@
+// Known-answer tests, which can be run at runtime (e.g. as power-on self-test)
+
+use transformations::g_N;
+use {StreebogHasher, StreebogHasher256, StreebogHasher512};
+
+// Messages M1 and M2 from GOST R 34.11-2012, appendix A
+static M1: &[u8] = b"012345678901234567890123456789012345678901234567890123456789012";
+static M2: [u8; 72] = [0xd1, 0xe5, 0x20, 0xe2, 0xe5, 0xf2, 0xf0, 0xe8, 0x2c, 0x20, 0xd1, 0xf2,
+                      0xf0, 0xe8, 0xe1, 0xee, 0xe6, 0xe8, 0x20, 0xe2, 0xed, 0xf3, 0xf6, 0xe8,
+                      0x2c, 0x20, 0xe2, 0xe5, 0xfe, 0xf2, 0xfa, 0x20, 0xf1, 0x20, 0xec, 0xee,
+                      0xf0, 0xff, 0x20, 0xf1, 0xf2, 0xf0, 0xe5, 0xeb, 0xe0, 0xec, 0xe8, 0x20,
+                      0xed, 0xe0, 0x20, 0xf5, 0xf0, 0xe0, 0xe1, 0xf0, 0xfb, 0xff, 0x20, 0xef,
+                      0xeb, 0xfa, 0xea, 0xfb, 0x20, 0xc8, 0xe3, 0xee, 0xf0, 0xe5, 0xe2, 0xfb];
+
+// Digests are in big-endian byte order (as returned by get_result)
+static H512_M1: [u8; 64] = [0x48, 0x6f, 0x64, 0xc1, 0x91, 0x78, 0x79, 0x41, 0x7f, 0xef, 0x08, 0x2b,
+                           0x33, 0x81, 0xa4, 0xe2, 0x11, 0xc3, 0x24, 0xf0, 0x74, 0x65, 0x4c, 0x38,
+                           0x82, 0x3a, 0x7b, 0x76, 0xf8, 0x30, 0xad, 0x00, 0xfa, 0x1f, 0xba, 0xe4,
+                           0x2b, 0x12, 0x85, 0xc0, 0x35, 0x2f, 0x22, 0x75, 0x24, 0xbc, 0x9a, 0xb1,
+                           0x62, 0x54, 0x28, 0x8d, 0xd6, 0x86, 0x3d, 0xcc, 0xd5, 0xb9, 0xf5, 0x4a,
+                           0x1a, 0xd0, 0x54, 0x1b];
+static H256_M1: [u8; 32] = [0x00, 0x55, 0x7b, 0xe5, 0xe5, 0x84, 0xfd, 0x52, 0xa4, 0x49, 0xb1, 0x6b,
+                           0x02, 0x51, 0xd0, 0x5d, 0x27, 0xf9, 0x4a, 0xb7, 0x6c, 0xba, 0xa6, 0xda,
+                           0x89, 0x0b, 0x59, 0xd8, 0xef, 0x1e, 0x15, 0x9d];
+static H512_M2: [u8; 64] = [0x28, 0xfb, 0xc9, 0xba, 0xda, 0x03, 0x3b, 0x14, 0x60, 0x64, 0x2b, 0xdc,
+                           0xdd, 0xb9, 0x0c, 0x3f, 0xb3, 0xe5, 0x6c, 0x49, 0x7c, 0xcd, 0x0f, 0x62,
+                           0xb8, 0xa2, 0xad, 0x49, 0x35, 0xe8, 0x5f, 0x03, 0x76, 0x13, 0x96, 0x6d,
+                           0xe4, 0xee, 0x00, 0x53, 0x1a, 0xe6, 0x0f, 0x3b, 0x5a, 0x47, 0xf8, 0xda,
+                           0xe0, 0x69, 0x15, 0xd5, 0xf2, 0xf1, 0x94, 0x99, 0x6f, 0xca, 0xbf, 0x26,
+                           0x22, 0xe6, 0x88, 0x1e];
+static H256_M2: [u8; 32] = [0x50, 0x8f, 0x7e, 0x55, 0x3c, 0x06, 0x50, 0x1d, 0x74, 0x9a, 0x66, 0xfc,
+                           0x28, 0xc6, 0xca, 0xc0, 0xb0, 0x05, 0x74, 0x6d, 0x97, 0x53, 0x7f, 0xa8,
+                           0x5d, 0x9e, 0x40, 0x90, 0x4e, 0xfe, 0xd2, 0x9d];
+
+// Result of the first compression of M1 (with zero IV and counter), which involves all core
+// transformations and tables
+static G_M1: [u8; 64] = [0xe2, 0xda, 0x3b, 0x6b, 0x73, 0xe4, 0xfe, 0x05, 0xd9, 0xf5, 0xb1, 0x3f,
+                         0x79, 0x35, 0x41, 0x95, 0x5c, 0x81, 0x50, 0x2c, 0x52, 0x0f, 0xed, 0xd3,
+                         0xc5, 0xba, 0xbb, 0x8c, 0x90, 0xf6, 0x54, 0x27, 0xbd, 0x8e, 0x73, 0x33,
+                         0xdb, 0x8a, 0x48, 0x26, 0xa6, 0xa9, 0x5a, 0x44, 0x41, 0x66, 0xa8, 0x17,
+                         0x38, 0x4f, 0x39, 0x21, 0xaf, 0x34, 0xea, 0x91, 0x11, 0xcb, 0x2c, 0x81,
+                         0xf8, 0x2c, 0x10, 0xfd];
+
+fn check512(message: &[u8], should_be: &[u8; 64]) -> bool {
+    let mut hasher = StreebogHasher512::new();
+    hasher.update(message);
+    hasher.finish();
+    hasher.result == *should_be
+}
+
+fn check256(message: &[u8], should_be: &[u8; 32]) -> bool {
+    let mut hasher = StreebogHasher256::new();
+    hasher.update(message);
+    hasher.finish();
+    hasher.result == *should_be
+}
+
+/// Runs known-answer tests (examples from GOST R 34.11-2012 for both digest sizes) and returns
+/// whether all of them passed (it also includes check of core transformations, which is run by
+/// hashers with `fips` feature).
+///
+/// It is intended for self-verification at runtime (e.g. FIPS-style power-on self-test), so
+/// application can refuse to proceed if the implementation is broken.
+pub fn self_test() -> bool {
+    check_transformations() && check512(M1, &H512_M1) && check256(M1, &H256_M1)
+        && check512(&M2, &H512_M2) && check256(&M2, &H256_M2)
+}
+
+/// Runs quick known-answer test of core transformations (one compression function invocation)
+/// and returns whether it passed.
+pub fn check_transformations() -> bool {
+    let mut block = [0u8; 64];
+    block[..63].copy_from_slice(M1);
+    block[63] = 0x1;
+    g_N([0u8; 64], [0u8; 64], block)[..] == G_M1[..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_test() {
+        assert!(self_test());
+    }
+
+    #[test]
+    fn test_check_transformations() {
+        assert!(check_transformations());
+    }
+}
@@ -0,0 +1,142 @@
+// Key derivation functions from R 50.1.113-2016 and R 50.1.111-2016 (RFC 7836)
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use hmac::{Hmac256, Hmac512};
+
+// HMAC_GOSTR3411_2012_256 of parts of message, result is in byte-string order
+pub(crate) fn hmac256(key: &[u8], message: &[&[u8]]) -> [u8; 32] {
+    let mut mac = Hmac256::new(key);
+    for part in message.iter() {
+        mac.update(part);
+    }
+    mac.finalize()
+}
+
+/// Derives 256-bit key from key `key` with function KDF_GOSTR3411_2012_256 (R 50.1.113-2016,
+/// RFC 7836), i.e. `HMAC_GOSTR3411_2012_256(key, 0x01 | label | 0x00 | seed | 0x01 | 0x00)`.
+///
+/// **Note!** Result is in byte-string order (as in RFC 7836 test vectors), which is reversed
+/// comparing to `get_result`.
+pub fn kdf_gostr3411_2012_256(key: &[u8], label: &[u8], seed: &[u8]) -> [u8; 32] {
+    hmac256(key, &[&[0x01], label, &[0x00], seed, &[0x01, 0x00]])
+}
+
+/// Derives one 256-bit subkey per label from master key with `kdf_gostr3411_2012_256` (with empty
+/// seed), e.g. separate keys for encryption and authentication.
+///
+/// Subkeys for distinct labels are independent: knowing some of them doesn't reveal master key
+/// or other subkeys. The same master key and label always produce the same subkey.
+#[cfg(feature = "alloc")]
+pub fn derive_subkeys(master: &[u8], labels: &[&[u8]]) -> Vec<[u8; 32]> {
+    labels.iter().map(|label| kdf_gostr3411_2012_256(master, label, &[])).collect()
+}
+
+/// Derives key from password with PBKDF2 (RFC 8018) using HMAC_GOSTR3411_2012_512 as PRF
+/// (R 50.1.111-2016, RFC 7836). Fills the whole `out`, which may be of any length.
+///
+/// # Panics
+///
+/// Panics if `iterations` is 0 or `out` is longer than `(2^32 - 1) * 64` bytes (limit of RFC 8018,
+/// as blocks are numbered by 32-bit counter).
+pub fn pbkdf2_streebog512(password: &[u8], salt: &[u8], iterations: u32, out: &mut [u8]) {
+    assert!(iterations > 0, "iteration count must be positive");
+    assert!(out.len() as u64 <= u32::MAX as u64 * 64, "derived key is too long");
+    let prf = Hmac512::new(password);
+    for (i, chunk) in out.chunks_mut(64).enumerate() {
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&(i as u32 + 1).to_be_bytes());
+        let mut u = mac.finalize();
+        let mut t = u;
+        for _ in 1..iterations {
+            let mut mac = prf.clone();
+            mac.update(&u);
+            u = mac.finalize();
+            for j in t.iter_mut().zip(u.iter()) {
+                *j.0 ^= *j.1;
+            }
+        }
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example from R 50.1.113-2016 (RFC 7836, appendix A)
+    static K: [u8; 32] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b,
+                          0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+                          0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f];
+    static KDF_256: [u8; 32] = [0xa1, 0xaa, 0x5f, 0x7d, 0xe4, 0x02, 0xd7, 0xb3, 0xd3, 0x23, 0xf2,
+                                0x99, 0x1c, 0x8d, 0x45, 0x34, 0x01, 0x31, 0x37, 0x01, 0x0a, 0x83,
+                                0x75, 0x4f, 0xd0, 0xaf, 0x6d, 0x7c, 0xd4, 0x92, 0x2e, 0xd9];
+
+    #[test]
+    fn test_kdf_gostr3411_2012_256() {
+        let label = [0x26, 0xbd, 0xb8, 0x78];
+        let seed = [0xaf, 0x21, 0x43, 0x41, 0x45, 0x65, 0x63, 0x78];
+        assert_eq!(kdf_gostr3411_2012_256(&K, &label, &seed), KDF_256);
+
+        // Label and seed are not limited to block size
+        let label = [0x11u8; 100];
+        let seed = [0x22u8; 200];
+        let mut mac = Hmac256::new(&K);
+        mac.update(&[&[0x01], &label[..], &[0x00], &seed[..], &[0x01, 0x00]].concat());
+        assert_eq!(kdf_gostr3411_2012_256(&K, &label, &seed), mac.finalize());
+    }
+
+    #[test]
+    fn test_pbkdf2_streebog512() {
+        // Test vectors from R 50.1.111-2016 (RFC 7836, appendix B)
+        let mut out = [0u8; 64];
+        pbkdf2_streebog512(b"password", b"salt", 1, &mut out);
+        assert_eq!(out[..8], [0x64, 0x77, 0x0a, 0xf7, 0xf7, 0x48, 0xc3, 0xb1]);
+        assert_eq!(out[56..], [0xb7, 0xee, 0xcd, 0xdb, 0xf9, 0xa1, 0x6b, 0x47]);
+        pbkdf2_streebog512(b"password", b"salt", 2, &mut out);
+        assert_eq!(out[..8], [0x5a, 0x58, 0x5b, 0xaf, 0xdf, 0xbb, 0x6e, 0x88]);
+        assert_eq!(out[56..], [0x8d, 0x3c, 0x42, 0xba, 0x66, 0xd3, 0x48, 0xde]);
+
+        // Output longer than one block starts with the same block
+        let mut long_out = [0u8; 100];
+        pbkdf2_streebog512(b"password", b"salt", 2, &mut long_out);
+        assert_eq!(long_out[..64], out[..]);
+    }
+
+    // Too slow in debug builds (especially with `ct`), run with `cargo test --release -- --ignored`
+    #[test]
+    #[ignore]
+    fn test_pbkdf2_streebog512_4096_iterations() {
+        // Test vectors from R 50.1.111-2016 (RFC 7836, appendix B)
+        let mut out = [0u8; 64];
+        pbkdf2_streebog512(b"password", b"salt", 4096, &mut out);
+        assert_eq!(out[..8], [0xe5, 0x2d, 0xeb, 0x9a, 0x2d, 0x2a, 0xaf, 0xf4]);
+        assert_eq!(out[56..], [0x3c, 0x2c, 0xda, 0xf1, 0xd9, 0xab, 0x86, 0xc3]);
+
+        // Output longer than one block
+        let mut out = [0u8; 100];
+        pbkdf2_streebog512(b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                           4096, &mut out);
+        assert_eq!(out[..8], [0xb2, 0xd8, 0xf1, 0x24, 0x5f, 0xc4, 0xd2, 0x92]);
+        assert_eq!(out[60..68], [0x40, 0x15, 0xdf, 0xc2, 0xbd, 0x24, 0x21, 0xee]);
+        assert_eq!(out[92..], [0xcf, 0x9c, 0xc5, 0x2a, 0x2b, 0xaa, 0x2d, 0x3a]);
+    }
+
+    #[test]
+    #[should_panic(expected = "iteration count must be positive")]
+    fn test_pbkdf2_streebog512_zero_iterations() {
+        pbkdf2_streebog512(b"password", b"salt", 0, &mut [0u8; 64]);
+    }
+
+    #[test]
+    fn test_derive_subkeys() {
+        let keys = derive_subkeys(&K, &[b"enc", b"mac", b"enc"]);
+        assert_eq!(keys.len(), 3);
+        assert_ne!(keys[0], keys[1]);
+        assert_eq!(keys[0], keys[2]);
+        assert_eq!(keys[1], kdf_gostr3411_2012_256(&K, b"mac", &[]));
+        assert!(derive_subkeys(&K, &[]).is_empty());
+    }
+}
@@ -5,27 +5,199 @@
 //! _Information Technology - Cryptographic Information Security -
 //! Hash Function_ aka _Streebog_ with digest sizes 256 and 512 bit
 //! (https://www.tc26.ru/en/standard/gost/GOST_R_34_11-2012_eng.pdf).
+//!
+//! # Examples
+//!
+//! Hashing one buffer:
+//!
+//! ```
+//! use streebog_hash::{hash256, hash512};
+//! let digest512 = hash512(b"some data");
+//! let digest256 = hash256(b"some data");
+//! ```
+//!
+//! Hashing data, which arrives by parts:
+//!
+//! ```
+//! use streebog_hash::*;
+//! let mut hasher = StreebogHasher512::new();
+//! hasher.update(b"some ");
+//! hasher.update(b"data");
+//! hasher.finish();
+//! assert_eq!(&hasher.get_result()[..], &hash512(b"some data")[..]);
+//! ```
+//!
+//! # Features
+//!
+//! * `std` (default) - support of standard library: I/O (`io::Write` for hashers,
+//!   `update_from_reader`, `TeeHasher`, `verify_checksum_file` etc.) and channels. Implies
+//!   `alloc`. Without it crate is `#![no_std]` (e.g. for bare-metal targets), and core API
+//!   (`update`, `finish`, `finalize`, `hash512`, `hash256` etc.) is still available.
+//! * `alloc` - functions, which return heap-allocated values (`get_result`, `get_result_str`,
+//!   `fingerprint`, `with_prefix`, `mgf1_streebog512` etc.), for `no_std` targets with global
+//!   allocator. Use `default-features = false, features = ["alloc", "profile-speed"]` for them.
+//! * `profile-speed` (default) - build optimized for throughput: linear transformation L uses
+//!   precomputed 16 KiB table, embedded into binary.
+//! * `profile-size` - build optimized for size: L multiplies by 64x64-bit matrix bit by bit, so
+//!   there is no table neither in binary nor in memory (implies `runtime-tables`, which then has
+//!   no effect). Binary is about 14 KiB smaller, but hashing is about 6 times slower. It takes
+//!   precedence over `profile-speed`, so use it with `default-features = false`. Public API is
+//!   the same for both profiles.
+//! * `ct` - use constant-time S-box and linear transformation L (no table lookups indexed by data,
+//!   so it resists cache-timing attacks when hashing secret data, e.g. in HMAC and KDF, but it is
+//!   much slower than default tables). It takes precedence over both profiles.
+//! * `runtime-tables` - don't embed precomputed 16 KiB table of linear transformation into binary,
+//!   compute it at first use instead (smaller binary at the cost of one-time startup work).
+//! * `rayon` - hash independent records in parallel in `hash_fixed_records512` and segments in
+//!   `tree::tree_hash512`.
+//! * `digest` - implement traits of RustCrypto [`digest`](https://docs.rs/digest) crate, so hashers
+//!   can be used with generic code like `hmac::SimpleHmac<StreebogHasher256>`. Block-level
+//!   `core_api` is implemented by `Streebog512Core` and `Streebog256Core`, use their wrappers
+//!   `Streebog512` and `Streebog256` with `hmac::Hmac` (e.g. `hmac::Hmac<Streebog256>`), because
+//!   `CoreProxy` is sealed in `digest`. Note that these traits produce
+//!   result in byte-string order (as other RustCrypto hashes and RFC 7836 test vectors), which is
+//!   reversed comparing to `get_result`.
+//! * `legacy-insecure-mac` - secret-prefix MAC helpers `prefix_mac512` and `prefix_mac256`
+//!   (`H(key || message)`) for compatibility with legacy protocols. Use HMAC (`hmac::Hmac512` or
+//!   `hmac::Hmac256`) for new code.
+//! * `fips` - run quick known-answer test of core transformations (one compression of example
+//!   from the standard) whenever hasher is created or reset, and panic if it fails. It is a
+//!   continuous self-test in the spirit of FIPS 140 conditional tests: it detects corruption of
+//!   tables in memory (bit-flips, tampering) in long-running processes before they produce wrong
+//!   digests, at the cost of one extra compression per hasher. It doesn't make crate certified.
+//! * `low-level` - non-standard functions like `compress_only`, which expose intermediate values
+//!   of algorithm for verification against other implementations, and finalization with domain
+//!   tag `StreebogHasher512::finalize_tagged`. Their output is **not** a Streebog digest.
+//! * `rand` - implement `rand_core::RngCore` for `StreebogRng`.
+//! * `heapless` - methods `get_result_str_heapless` and `into_pending_heapless`, which return
+//!   [`heapless`](https://docs.rs/heapless) containers of fixed capacity instead of `String` and
+//!   `Vec` (hashers themselves never allocate, so it allows getting output without allocator).
+//! * `serde` - implement `Serialize` and `Deserialize` of [`serde`](https://docs.rs/serde) for
+//!   hashers, so intermediate state can be saved and hashing resumed later (e.g. for interrupted
+//!   uploads). Serialized state contains buffered data, i.e. the tail of the message.
+//! * `zeroize` - wipe internal state and result of hashers when they are dropped or reset (via
+//!   [`zeroize`](https://docs.rs/zeroize)), e.g. when hashing secret keys.
 
+#![no_std]
 #![allow(non_snake_case)]
 #![allow(non_upper_case_globals)]
 
+#[cfg(feature = "alloc")]
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "digest")]
+extern crate digest;
+#[cfg(feature = "heapless")]
+extern crate heapless;
+#[cfg(feature = "rand")]
+extern crate rand_core;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(test)]
+#[macro_use]
+extern crate proptest;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+#[cfg(feature = "zeroize")]
+extern crate zeroize;
+#[cfg(all(test, feature = "digest"))]
+extern crate hmac as hmac_crate;
+
+#[cfg(feature = "std")]
+mod checksum;
+#[cfg(feature = "alloc")]
+mod config;
 mod const_data;
+pub mod diagnostics;
+#[cfg(feature = "digest")]
+mod digest_impl;
+mod error;
+pub mod hmac;
+mod kat;
+mod kdf;
+pub mod otp;
+mod output;
+#[cfg(any(not(any(feature = "runtime-tables", feature = "ct")), test))]
 mod precomp_data;
+#[cfg(feature = "alloc")]
+mod rng;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod std_hasher;
+#[cfg(feature = "std")]
+mod tee;
 mod transformations;
+pub mod tree;
 
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cmp::{Eq, PartialEq};
+use core::ops::Range;
+use core::str::{self, Utf8Error};
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::io::{self, BufRead, IoSlice, Read, Write};
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "std")]
+use std::sync::mpsc::{SendError, Sender};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use transformations::*;
-use std::cmp::{Eq, PartialEq};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "std")]
+pub use checksum::verify_checksum_file;
+#[cfg(feature = "alloc")]
+pub use config::StreebogConfig;
+#[cfg(feature = "digest")]
+pub use digest_impl::{Streebog256, Streebog256Core, Streebog512, Streebog512Core};
+pub use error::{HasherError, StreebogError};
+pub use kat::self_test;
+#[cfg(feature = "alloc")]
+pub use kdf::derive_subkeys;
+pub use kdf::{kdf_gostr3411_2012_256, pbkdf2_streebog512};
+pub use output::{Streebog256Digest, Streebog512Digest};
+#[cfg(feature = "alloc")]
+pub use rng::StreebogRng;
+pub use std_hasher::StreebogStdHasher;
+#[cfg(feature = "std")]
+pub use tee::{CapturingHasher, TeeHasher};
 
+#[derive(Clone, Copy)]
 enum StreebogHasherDigest {
     StreebogHasher256,
     StreebogHasher512,
 }
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct StreebogHasherCtx {
+    #[cfg_attr(feature = "serde", serde(with = "serde_impl"))]
     hash: [u8; 64],
-    N: [u8; 64],
-    sigma: [u8; 64],
-    data: Vec<u8>,
+    // Counter and checksum are little-endian 64-bit words for fast addition
+    N: [u64; 8],
+    sigma: [u64; 8],
+    // Staging buffer for data, which is not hashed yet (first `data_len` bytes are used)
+    #[cfg_attr(feature = "serde", serde(with = "serde_impl"))]
+    data: [u8; 64],
+    data_len: usize,
+    // Number of g_N invocations
+    blocks: u64,
+    // Number of bytes written
+    absorbed: u64,
 }
 
 /// A trait which represents the ability to hash an arbitrary stream of bytes with Streebog
@@ -35,23 +207,164 @@ pub trait StreebogHasher {
     fn new() -> Self;
     /// Writes some data into this hasher.
     fn update(&mut self, data_chunk: &[u8]);
+    /// Writes some data into this hasher, the same as `update`, but returns error instead of
+    /// silently ignoring data: `StreebogError::AlreadyFinished`, if hasher is finished, or
+    /// `StreebogError::LengthExceeded`, if total length of data exceeds limit of `with_max_len`
+    /// (in both cases data is not hashed).
+    ///
+    /// Default implementation calls `update` and never fails (it can't tell whether data was
+    /// rejected), hashers of this crate override it.
+    fn try_update(&mut self, data_chunk: &[u8]) -> Result<(), StreebogError> {
+        self.update(data_chunk);
+        Ok(())
+    }
+    /// Writes some data into this hasher (the same as `update`) and returns the hasher, so calls
+    /// can be chained: `hasher.with(a).with(b).finish()`.
+    fn with(&mut self, data_chunk: &[u8]) -> &mut Self {
+        self.update(data_chunk);
+        self
+    }
+    /// Writes some data into this hasher (the same as `update`, but accepts both borrowed and
+    /// owned data).
+    #[cfg(feature = "alloc")]
+    fn update_cow(&mut self, data_chunk: Cow<[u8]>) {
+        self.update(&data_chunk);
+    }
+    /// Writes UTF-8 bytes of text into this hasher.
+    fn update_text(&mut self, s: &str) {
+        self.update(s.as_bytes());
+    }
+    /// Writes 64-bit integer into this hasher as 8 bytes in big-endian order (canonical encoding of
+    /// counters in this crate, e.g. for counter-mode constructions).
+    fn update_u64_be(&mut self, n: u64) {
+        self.update(&n.to_be_bytes());
+    }
+    /// Writes 32-bit integer into this hasher as 4 bytes in big-endian order (canonical encoding of
+    /// counters in this crate, e.g. in `mgf1_streebog512` and `StreebogRng`).
+    fn update_u32_be(&mut self, n: u32) {
+        self.update(&n.to_be_bytes());
+    }
+    /// Writes every integer of slice into this hasher as 4 bytes in little-endian order (so
+    /// result doesn't depend on byte order of platform).
+    fn update_u32_slice_le(&mut self, values: &[u32]) {
+        for value in values.iter() {
+            self.update(&value.to_le_bytes());
+        }
+    }
+    /// Writes every integer of slice into this hasher as 4 bytes in big-endian order (so
+    /// result doesn't depend on byte order of platform).
+    fn update_u32_slice_be(&mut self, values: &[u32]) {
+        for value in values.iter() {
+            self.update(&value.to_be_bytes());
+        }
+    }
+    /// Writes every integer of slice into this hasher as 8 bytes in little-endian order (so
+    /// result doesn't depend on byte order of platform).
+    fn update_u64_slice_le(&mut self, values: &[u64]) {
+        for value in values.iter() {
+            self.update(&value.to_le_bytes());
+        }
+    }
+    /// Writes every integer of slice into this hasher as 8 bytes in big-endian order (so
+    /// result doesn't depend on byte order of platform).
+    fn update_u64_slice_be(&mut self, values: &[u64]) {
+        for value in values.iter() {
+            self.update(&value.to_be_bytes());
+        }
+    }
+    /// Reads all data from reader (until EOF) and writes it into this hasher without finishing,
+    /// so it can be mixed with other updates. Returns number of bytes read.
+    ///
+    /// Data is read by chunks of 64 KiB, reads interrupted by signal are retried. On error data,
+    /// which was read before it, remains written into hasher.
+    #[cfg(feature = "std")]
+    fn update_from_reader<R: Read>(&mut self, reader: &mut R) -> io::Result<u64> {
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut total = 0u64;
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => return Ok(total),
+                Ok(n) => {
+                    self.update(&buffer[..n]);
+                    total += n as u64;
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
     /// Completes a round of hashing.
     fn finish(&mut self);
+    /// Finishes hashing, the same as `finish`, but returns error `StreebogError::AlreadyFinished`,
+    /// if hasher is already finished, or `StreebogError::LengthExceeded`, if limit of
+    /// `with_max_len` was exceeded.
+    ///
+    /// Default implementation calls `finish` and never fails, hashers of this crate override it.
+    fn try_finish(&mut self) -> Result<(), StreebogError> {
+        self.finish();
+        Ok(())
+    }
     /// Returns result of hashing as Box<[u8]> (result is big-endian, i.e. bytes arranged in the
     /// same order as in String representation).
     ///
     /// If hasher is not finished (i.e. finish is not called), it returns empty array.
+    #[cfg(feature = "alloc")]
     fn get_result(&self) -> Box<[u8]>;
     /// Returns result of hashing as String.
     ///
     /// If hasher is not finished (i.e. finish is not called), it returns empty String.
+    #[cfg(feature = "alloc")]
     fn get_result_str(&self) -> String;
+    /// Writes result of hashing (big-endian, as returned by `get_result`) into beginning of `out`
+    /// without allocation and returns number of bytes written.
+    ///
+    /// Returns `StreebogError::InvalidState` if hasher is not finished, or
+    /// `StreebogError::InvalidLength` (with digest size as expected length) if `out` is shorter
+    /// than digest.
+    ///
+    /// Default implementation copies result of `get_result` (so it allocates), hashers of this
+    /// crate override it. Without `alloc` feature it has no default implementation.
+    #[cfg(feature = "alloc")]
+    fn get_result_into(&self, out: &mut [u8]) -> Result<usize, StreebogError> {
+        let result = self.get_result();
+        if result.is_empty() {
+            return Err(StreebogError::InvalidState);
+        }
+        if out.len() < result.len() {
+            return Err(StreebogError::InvalidLength {
+                expected: result.len(),
+                actual: out.len(),
+            });
+        }
+        out[..result.len()].copy_from_slice(&result);
+        Ok(result.len())
+    }
+    /// Writes result of hashing (big-endian) into beginning of `out` without allocation and
+    /// returns number of bytes written.
+    ///
+    /// Returns `StreebogError::InvalidState` if hasher is not finished, or
+    /// `StreebogError::InvalidLength` (with digest size as expected length) if `out` is shorter
+    /// than digest.
+    #[cfg(not(feature = "alloc"))]
+    fn get_result_into(&self, out: &mut [u8]) -> Result<usize, StreebogError>;
     /// Reset hasher to default state and mark as not finished.
     ///
     /// **Note!** After calling this all processed data will be lost!
     fn reset(&mut self);
 }
 
+/// A trait which exposes parameters of Streebog variant, so generic constructions (HMAC, KDF etc.)
+/// don't have to hardcode them.
+pub trait StreebogParams {
+    /// Number of rounds of block cipher E in compression function (the same for both variants).
+    const ROUNDS: usize = 12;
+    /// Size of message block in bytes, processed by one compression function invocation (the same
+    /// for both variants).
+    const BLOCK_SIZE: usize = 64;
+    /// Size of digest in bytes.
+    const OUTPUT_SIZE: usize;
+}
+
 /// An implementation of Streebog algorithm with digest size 512 bit.
 ///
 /// # Examples
@@ -59,45 +372,68 @@ pub trait StreebogHasher {
 /// ```
 /// use streebog_hash::*;
 /// let mut hasher = StreebogHasher512::new();
-/// let data = [0xfc as u8; 128];
+/// let data = [0xfcu8; 128];
 /// hasher.update(&data[0..64]);
 /// hasher.update(&data[64..128]);
 /// hasher.finish();
 /// let result = hasher.get_result();
 /// println!("{}", hasher.get_result_str());
 /// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "serde_impl::HasherState512"))]
 pub struct StreebogHasher512 {
     ctx: StreebogHasherCtx,
     is_finished: bool,
+    #[cfg_attr(feature = "serde", serde(with = "serde_impl"))]
     result: [u8; 64],
+    // State after absorbing prefix (see `with_prefix`)
+    #[cfg(feature = "alloc")]
+    prefix_ctx: Option<Box<StreebogHasherCtx>>,
+    // Limit of total length of data (see `with_max_len`) and whether it was exceeded
+    max_len: u64,
+    len_exceeded: bool,
 }
 
 impl StreebogHasher for StreebogHasher512 {
     fn new() -> StreebogHasher512 {
+        fips_self_test();
         StreebogHasher512 {
-            ctx: StreebogHasherCtx {
-                hash: [0 as u8; 64],
-                N: [0 as u8; 64],
-                sigma: [0 as u8; 64],
-                data: Vec::new(),
-            },
+            ctx: StreebogHasherCtx::new(0),
             is_finished: false,
-            result: [0 as u8; 64],
+            result: [0u8; 64],
+            #[cfg(feature = "alloc")]
+            prefix_ctx: None,
+            max_len: u64::MAX,
+            len_exceeded: false,
         }
     }
 
     fn update(&mut self, data_chunk: &[u8]) {
         if !self.is_finished {
-            streebog_update(&mut self.ctx, data_chunk);
+            self.absorb(data_chunk);
+        }
+    }
+
+    fn try_update(&mut self, data_chunk: &[u8]) -> Result<(), StreebogError> {
+        if self.is_finished {
+            return Err(StreebogError::AlreadyFinished);
+        }
+        self.update(data_chunk);
+        if self.len_exceeded {
+            Err(StreebogError::LengthExceeded { max: self.max_len })
+        } else {
+            Ok(())
         }
     }
 
     fn finish(&mut self) {
-        if !self.is_finished {
+        if !self.is_finished && !self.len_exceeded {
             for i in self.result
                     .iter_mut()
                     .zip(streebog_finish(&mut self.ctx,
-                                         StreebogHasherDigest::StreebogHasher512)
+                                         StreebogHasherDigest::StreebogHasher512,
+                                         0)
                                  .iter()
                                  .rev()) {
                 *i.0 = *i.1;
@@ -106,6 +442,18 @@ impl StreebogHasher for StreebogHasher512 {
         };
     }
 
+    fn try_finish(&mut self) -> Result<(), StreebogError> {
+        if self.is_finished {
+            return Err(StreebogError::AlreadyFinished);
+        }
+        if self.len_exceeded {
+            return Err(StreebogError::LengthExceeded { max: self.max_len });
+        }
+        self.finish();
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
     fn get_result(&self) -> Box<[u8]> {
         if self.is_finished {
             Box::new(self.result)
@@ -114,6 +462,7 @@ impl StreebogHasher for StreebogHasher512 {
         }
     }
 
+    #[cfg(feature = "alloc")]
     fn get_result_str(&self) -> String {
         if self.is_finished {
             let mut result_string = String::from("0x");
@@ -126,13 +475,410 @@ impl StreebogHasher for StreebogHasher512 {
         }
     }
 
+    fn get_result_into(&self, out: &mut [u8]) -> Result<usize, StreebogError> {
+        if !self.is_finished {
+            return Err(StreebogError::InvalidState);
+        }
+        if out.len() < self.result.len() {
+            return Err(StreebogError::InvalidLength {
+                expected: self.result.len(),
+                actual: out.len(),
+            });
+        }
+        out[..self.result.len()].copy_from_slice(&self.result);
+        Ok(self.result.len())
+    }
+
     fn reset(&mut self) {
+        fips_self_test();
+        #[cfg(feature = "zeroize")]
+        self.ctx.zeroize();
         self.is_finished = false;
-        self.ctx.hash = [0 as u8; 64];
-        self.ctx.N = [0 as u8; 64];
-        self.ctx.sigma = [0 as u8; 64];
-        self.ctx.data.clear();
-        self.result = [0 as u8; 64];
+        self.ctx.hash = [0u8; 64];
+        self.ctx.N = [0u64; 8];
+        self.ctx.sigma = [0u64; 8];
+        self.ctx.data_len = 0;
+        self.ctx.blocks = 0;
+        self.ctx.absorbed = 0;
+        self.len_exceeded = false;
+        self.result = [0u8; 64];
+    }
+}
+
+impl Default for StreebogHasher512 {
+    fn default() -> StreebogHasher512 {
+        StreebogHasher512::new()
+    }
+}
+
+impl StreebogParams for StreebogHasher512 {
+    const OUTPUT_SIZE: usize = 64;
+}
+
+/// With `zeroize` feature internal state and result are wiped on drop (e.g. for keyed hashing).
+#[cfg(feature = "zeroize")]
+impl Drop for StreebogHasher512 {
+    fn drop(&mut self) {
+        self.result.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for StreebogHasher512 {}
+
+/// Writing data is the same as `update`, so hasher can be used with `io::copy` and other generic
+/// code. Writing into finished hasher fails with error of kind `ErrorKind::Other` (as well as
+/// writing over limit of `with_max_len`), flushing does nothing.
+#[cfg(feature = "std")]
+impl Write for StreebogHasher512 {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.try_update(buf).map_err(io::Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl StreebogHasher512 {
+    /// Consumes hasher, finishes hashing (if not finished yet) and returns digest.
+    pub fn finalize(mut self) -> Streebog512Digest {
+        StreebogHasher::finish(&mut self);
+        Streebog512Digest(self.result)
+    }
+
+    /// Finishes hashing with non-standard finalization, which includes domain tag, and returns
+    /// result (in big-endian byte order, as returned by `get_result`), e.g. for building and
+    /// researching MAC variants on top of Streebog core.
+    ///
+    /// `domain_tag` is XORed into the most significant byte of the last (padded) message block
+    /// (i.e. bits `m[511..504]` in notation of the standard, which is the 64th byte of block in
+    /// order of data passed to `update`) after padding, before block is compressed and added to
+    /// checksum. Length counter is not affected. If the last block has 63 bytes of data, this byte
+    /// is padding marker `0x01`, so tag is XORed into marker. Tag 0 gives standard digest.
+    ///
+    /// **Note!** Output is **not** a Streebog digest (unless tag is 0). If hasher is already
+    /// finished, tag is ignored and existing result is returned.
+    #[cfg(feature = "low-level")]
+    pub fn finalize_tagged(&mut self, domain_tag: u8) -> [u8; 64] {
+        if !self.is_finished && !self.len_exceeded {
+            for i in self.result
+                    .iter_mut()
+                    .zip(streebog_finish(&mut self.ctx,
+                                         StreebogHasherDigest::StreebogHasher512,
+                                         domain_tag)
+                                 .iter()
+                                 .rev()) {
+                *i.0 = *i.1;
+            }
+            self.is_finished = true;
+        }
+        self.result
+    }
+
+    /// Consumes hasher, finishes hashing (if not finished yet) and returns result of hashing (in
+    /// big-endian byte order, as returned by `get_result`) together with total number of bytes
+    /// written since creation or last reset.
+    pub fn finalize_with_len(self) -> ([u8; 64], u64) {
+        let len = self.ctx.absorbed;
+        (self.finalize().0, len)
+    }
+
+    /// Finishes hashing (if not finished yet), resets hasher (the same as `reset`, so it can be
+    /// reused for the next message) and returns result of hashing (in big-endian byte order, as
+    /// returned by `get_result`).
+    ///
+    /// **Note!** If limit of `with_max_len` was exceeded, hasher can't be finished and zeroes are
+    /// returned.
+    pub fn finalize_reset(&mut self) -> [u8; 64] {
+        StreebogHasher::finish(self);
+        let result = self.result;
+        StreebogHasher::reset(self);
+        result
+    }
+
+    /// Consumes hasher, finishes hashing (if not finished yet) and sends result of hashing (in
+    /// big-endian byte order, as returned by `get_result`) to channel.
+    ///
+    /// Returns error (which contains digest), if receiver is disconnected.
+    #[cfg(feature = "std")]
+    pub fn finalize_send(self, tx: Sender<[u8; 64]>) -> Result<(), SendError<[u8; 64]>> {
+        tx.send(self.finalize().0)
+    }
+
+    /// Returns first `bytes` bytes of result of hashing (in big-endian byte order, as returned by
+    /// `get_result`) as lowercase hex without `0x` prefix, e.g. `fingerprint(4)` gives short
+    /// 8-character identifier for display. If `bytes` is greater than digest size, the whole
+    /// digest is returned.
+    ///
+    /// **Note!** Short fingerprint is intended only for display, it is not cryptographically
+    /// meaningful: collisions of few bytes can be easily found.
+    ///
+    /// If hasher is not finished (i.e. finish is not called), it returns empty String.
+    #[cfg(feature = "alloc")]
+    pub fn fingerprint(&self, bytes: usize) -> String {
+        if self.is_finished {
+            fingerprint_hex(&self.result, bytes)
+        } else {
+            String::new()
+        }
+    }
+
+    /// Creates new hasher, which accepts no more than `max` bytes of data in total (e.g. to limit
+    /// CPU time spent on hashing untrusted input). When data exceeding limit is written, it is
+    /// not hashed and hasher stops accepting any data (`try_update` returns error) until reset.
+    ///
+    /// **Note!** Hasher, which exceeded limit, can't be finished: `finish` does nothing (and
+    /// `get_result` returns empty result), `try_finish` returns error.
+    pub fn with_max_len(max: u64) -> StreebogHasher512 {
+        let mut hasher = StreebogHasher512::new();
+        hasher.max_len = max;
+        hasher
+    }
+
+    // Hashes data, if it doesn't exceed length limit, and returns whether it was hashed
+    fn absorb(&mut self, data: &[u8]) -> bool {
+        if self.len_exceeded || data.len() as u64 > self.max_len - self.ctx.absorbed {
+            self.len_exceeded = true;
+            return false;
+        }
+        streebog_update(&mut self.ctx, data);
+        true
+    }
+
+    /// Creates new hasher with `prefix` (e.g. key) already written into it. State after
+    /// absorbing prefix is saved, so hasher can be returned to it with `reset_to_prefix` without
+    /// hashing prefix again.
+    ///
+    /// **Note!** Saved state takes additional heap allocation of about 200 bytes plus length of
+    /// unhashed tail of prefix (less than 64 bytes).
+    #[cfg(feature = "alloc")]
+    pub fn with_prefix(prefix: &[u8]) -> StreebogHasher512 {
+        let mut hasher = StreebogHasher512::new();
+        streebog_update(&mut hasher.ctx, prefix);
+        hasher.prefix_ctx = Some(Box::new(hasher.ctx.clone()));
+        hasher
+    }
+
+    /// Reset hasher to state right after absorbing prefix, passed to `with_prefix`, and mark as
+    /// not finished, so next message can be hashed with the same prefix.
+    ///
+    /// If hasher was not created with `with_prefix`, it is the same as `reset`.
+    #[cfg(feature = "alloc")]
+    pub fn reset_to_prefix(&mut self) {
+        match self.prefix_ctx {
+            Some(ref prefix_ctx) => {
+                fips_self_test();
+                self.is_finished = false;
+                self.ctx.clone_from(prefix_ctx);
+                self.len_exceeded = false;
+                self.result = [0u8; 64];
+            }
+            None => StreebogHasher::reset(self),
+        }
+    }
+
+    /// Returns independent copy of this hasher (the same as `clone`), which is the way to fork
+    /// hashing: both original and branch can be updated with different data and finished
+    /// separately, without affecting each other. It is useful for hashing many messages with common
+    /// prefix (e.g. nodes of Merkle tree).
+    pub fn branch(&self) -> Self {
+        self.clone()
+    }
+
+    /// Encodes state of this hasher (intermediate hash, counters, buffered data and result, if
+    /// finished) into versioned byte layout, so hashing can be resumed later with `restore_state`
+    /// (e.g. in another process), without `serde`.
+    ///
+    /// **Note!** Prefix of `with_prefix` and limit of `with_max_len` are not saved. Saved state
+    /// contains buffered data, i.e. the tail of the message.
+    #[cfg(feature = "alloc")]
+    pub fn save_state(&self) -> Vec<u8> {
+        let result = if self.is_finished {
+            Some(&self.result[..])
+        } else {
+            None
+        };
+        save_ctx(&self.ctx, 64, result)
+    }
+
+    /// Restores hasher from state encoded by `StreebogHasher512::save_state`.
+    ///
+    /// Returns `StreebogError::UnsupportedVersion` for unknown layout version,
+    /// `StreebogError::InvalidState` for state of other variant (or malformed one) and
+    /// `StreebogError::InvalidLength` if state is truncated or has trailing bytes.
+    pub fn restore_state(bytes: &[u8]) -> Result<StreebogHasher512, HasherError> {
+        let mut hasher = StreebogHasher512::new();
+        let (ctx, is_finished) = restore_ctx(bytes, 64, &mut hasher.result)?;
+        hasher.ctx = ctx;
+        hasher.is_finished = is_finished;
+        Ok(hasher)
+    }
+
+    /// Returns owned copy of state of this hasher, which can be moved to another thread and
+    /// finished there independently of this hasher.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot(&self) -> SendableState {
+        SendableState {
+            ctx: self.ctx.clone(),
+            mode: StreebogHasherDigest::StreebogHasher512,
+            result: if self.is_finished {
+                Some(self.get_result())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Checks whether internal state (intermediate hash, counters and buffered data) of two
+    /// hashers is the same, e.g. to verify in tests, that different sequences of updates lead to
+    /// the same state.
+    ///
+    /// **Note!** This comparison is not constant-time, so it must not be used to verify secret
+    /// data.
+    pub fn state_eq(&self, other: &Self) -> bool {
+        self.is_finished == other.is_finished && self.ctx == other.ctx
+    }
+
+    /// Writes digest of previous message into this hasher to build hash chain.
+    ///
+    /// Canonical chaining convention of this crate is `link[0] = H(message[0])` and
+    /// `link[i] = H(link[i - 1] || message[i])`, i.e. previous digest is written first (before any
+    /// other data), in big-endian byte order (as returned by `get_result`), without any separator
+    /// (digest has fixed length, so there is no ambiguity). So it is the same as
+    /// `update(previous_digest)` on a fresh hasher.
+    pub fn chain_from(&mut self, previous_digest: &[u8; 64]) {
+        StreebogHasher::update(self, previous_digest);
+    }
+
+    /// Returns number of 64-byte blocks compressed so far (i.e. number of compression function
+    /// invocations, which is proportional to CPU cost of hashing).
+    ///
+    /// Finished hasher also counts the last padded block and the two finalization compressions.
+    pub fn blocks_compressed(&self) -> u64 {
+        self.ctx.blocks
+    }
+
+    /// Writes some data into this hasher and returns number of bytes, which are buffered, but not
+    /// hashed yet (it is always less than 64, because only full 64-byte blocks are hashed before
+    /// finish).
+    ///
+    /// If hasher is finished, data is ignored and it returns 0.
+    pub fn update_reporting(&mut self, data_chunk: &[u8]) -> usize {
+        if self.is_finished {
+            0
+        } else {
+            self.absorb(data_chunk);
+            self.ctx.data_len
+        }
+    }
+
+    /// Writes some data into this hasher and returns number of 64-byte blocks, which are
+    /// compressed as result of this call (0, if data is only buffered), e.g. for progress
+    /// reporting.
+    ///
+    /// If hasher is finished, data is ignored and it returns 0.
+    pub fn update_counting(&mut self, data_chunk: &[u8]) -> u64 {
+        let blocks = self.ctx.blocks;
+        StreebogHasher::update(self, data_chunk);
+        self.ctx.blocks - blocks
+    }
+
+    /// Finishes hashing (if not finished yet) and XORs result of hashing into accumulator `acc`.
+    ///
+    /// XOR is commutative and associative, so accumulator of set of elements doesn't depend on
+    /// order, in which their digests are added, and adding the same digest twice removes it (e.g.
+    /// for set reconciliation).
+    pub fn xor_into(&mut self, acc: &mut [u8; 64]) {
+        StreebogHasher::finish(self);
+        for i in acc.iter_mut().zip(self.result.iter()) {
+            *i.0 ^= *i.1;
+        }
+    }
+
+    /// Writes data into this hasher, but no more than needed to reach `total_expected` bytes
+    /// written in total since creation or last reset (including prefix of `with_prefix`), e.g.
+    /// for length-delimited messages, which arrive by parts. Returns number of consumed bytes and
+    /// whether `total_expected` bytes are written (so hasher is ready to finish).
+    ///
+    /// If hasher is finished, data is not consumed.
+    pub fn update_until(&mut self, data: &[u8], total_expected: u64) -> (usize, bool) {
+        let remaining = total_expected.saturating_sub(self.ctx.absorbed);
+        let len = if self.is_finished {
+            0
+        } else {
+            core::cmp::min(remaining, data.len() as u64) as usize
+        };
+        if !self.absorb(&data[..len]) {
+            return (0, false);
+        }
+        (len, self.ctx.absorbed >= total_expected)
+    }
+
+    /// Returns result of hashing as hex-encoded ASCII bytes without `0x` prefix.
+    ///
+    /// If hasher is not finished (i.e. finish is not called), it returns empty Vec.
+    #[cfg(feature = "alloc")]
+    pub fn to_hex_bytes(&self, uppercase: bool) -> Vec<u8> {
+        if self.is_finished {
+            hex_bytes(&self.result, uppercase)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Returns result of hashing as lowercase hex without `0x` prefix.
+    ///
+    /// If hasher is not finished (i.e. finish is not called), it returns empty String.
+    #[cfg(feature = "alloc")]
+    pub fn to_hex(&self) -> String {
+        hex_string(&self.to_hex_bytes(false))
+    }
+
+    /// Returns result of hashing as uppercase hex without `0x` prefix.
+    ///
+    /// If hasher is not finished (i.e. finish is not called), it returns empty String.
+    #[cfg(feature = "alloc")]
+    pub fn to_hex_upper(&self) -> String {
+        hex_string(&self.to_hex_bytes(true))
+    }
+
+    /// Consumes unfinished hasher and returns data, which was written into it, but not hashed
+    /// yet (i.e. the tail of data shorter than one 64-byte block).
+    ///
+    /// **Note!** Data from already compressed blocks can't be recovered.
+    ///
+    /// If hasher is finished, it returns empty Vec.
+    #[cfg(feature = "alloc")]
+    pub fn into_pending(self) -> Vec<u8> {
+        if self.is_finished {
+            Vec::new()
+        } else {
+            self.ctx.data[..self.ctx.data_len].to_vec()
+        }
+    }
+
+    /// The same as `get_result_str`, but returns string with fixed capacity, which doesn't
+    /// require allocator.
+    #[cfg(feature = "heapless")]
+    pub fn get_result_str_heapless(&self) -> heapless::String<HEX_STR_CAPACITY> {
+        if self.is_finished {
+            hex_str_heapless(&self.result)
+        } else {
+            heapless::String::new()
+        }
+    }
+
+    /// The same as `into_pending`, but returns vector with fixed capacity, which doesn't require
+    /// allocator.
+    #[cfg(feature = "heapless")]
+    pub fn into_pending_heapless(self) -> heapless::Vec<u8, BLOCK_CAPACITY> {
+        if self.is_finished {
+            heapless::Vec::new()
+        } else {
+            heapless::Vec::from_slice(&self.ctx.data[..self.ctx.data_len]).unwrap()
+        }
     }
 }
 
@@ -144,45 +890,67 @@ impl StreebogHasher for StreebogHasher512 {
 /// ```
 /// use streebog_hash::*;
 /// let mut hasher = StreebogHasher256::new();
-/// let data = [0xfc as u8; 128];
+/// let data = [0xfcu8; 128];
 /// hasher.update(&data[0..64]);
 /// hasher.update(&data[64..128]);
 /// hasher.finish();
 /// let result = hasher.get_result();
 /// println!("{}", hasher.get_result_str());
 /// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "serde_impl::HasherState256"))]
 pub struct StreebogHasher256 {
     ctx: StreebogHasherCtx,
     is_finished: bool,
     result: [u8; 32],
+    // State after absorbing prefix (see `with_prefix`)
+    #[cfg(feature = "alloc")]
+    prefix_ctx: Option<Box<StreebogHasherCtx>>,
+    // Limit of total length of data (see `with_max_len`) and whether it was exceeded
+    max_len: u64,
+    len_exceeded: bool,
 }
 
 impl StreebogHasher for StreebogHasher256 {
     fn new() -> StreebogHasher256 {
+        fips_self_test();
         StreebogHasher256 {
-            ctx: StreebogHasherCtx {
-                hash: [1 as u8; 64],
-                N: [0 as u8; 64],
-                sigma: [0 as u8; 64],
-                data: Vec::new(),
-            },
+            ctx: StreebogHasherCtx::new(1),
             is_finished: false,
-            result: [0 as u8; 32],
+            result: [0u8; 32],
+            #[cfg(feature = "alloc")]
+            prefix_ctx: None,
+            max_len: u64::MAX,
+            len_exceeded: false,
         }
     }
 
     fn update(&mut self, data_chunk: &[u8]) {
         if !self.is_finished {
-            streebog_update(&mut self.ctx, data_chunk);
+            self.absorb(data_chunk);
+        }
+    }
+
+    fn try_update(&mut self, data_chunk: &[u8]) -> Result<(), StreebogError> {
+        if self.is_finished {
+            return Err(StreebogError::AlreadyFinished);
+        }
+        self.update(data_chunk);
+        if self.len_exceeded {
+            Err(StreebogError::LengthExceeded { max: self.max_len })
+        } else {
+            Ok(())
         }
     }
 
     fn finish(&mut self) {
-        if !self.is_finished {
+        if !self.is_finished && !self.len_exceeded {
             for i in self.result
                     .iter_mut()
                     .zip(streebog_finish(&mut self.ctx,
-                                         StreebogHasherDigest::StreebogHasher256)
+                                         StreebogHasherDigest::StreebogHasher256,
+                                         0)
                                  .iter()
                                  .rev()) {
                 *i.0 = *i.1;
@@ -191,6 +959,18 @@ impl StreebogHasher for StreebogHasher256 {
         };
     }
 
+    fn try_finish(&mut self) -> Result<(), StreebogError> {
+        if self.is_finished {
+            return Err(StreebogError::AlreadyFinished);
+        }
+        if self.len_exceeded {
+            return Err(StreebogError::LengthExceeded { max: self.max_len });
+        }
+        self.finish();
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
     fn get_result(&self) -> Box<[u8]> {
         if self.is_finished {
             Box::new(self.result)
@@ -199,6 +979,7 @@ impl StreebogHasher for StreebogHasher256 {
         }
     }
 
+    #[cfg(feature = "alloc")]
     fn get_result_str(&self) -> String {
         if self.is_finished {
             let mut result_string = String::from("0x");
@@ -211,224 +992,2486 @@ impl StreebogHasher for StreebogHasher256 {
         }
     }
 
+    fn get_result_into(&self, out: &mut [u8]) -> Result<usize, StreebogError> {
+        if !self.is_finished {
+            return Err(StreebogError::InvalidState);
+        }
+        if out.len() < self.result.len() {
+            return Err(StreebogError::InvalidLength {
+                expected: self.result.len(),
+                actual: out.len(),
+            });
+        }
+        out[..self.result.len()].copy_from_slice(&self.result);
+        Ok(self.result.len())
+    }
+
     fn reset(&mut self) {
+        fips_self_test();
+        #[cfg(feature = "zeroize")]
+        self.ctx.zeroize();
         self.is_finished = false;
-        self.ctx.hash = [0 as u8; 64];
-        self.ctx.N = [0 as u8; 64];
-        self.ctx.sigma = [0 as u8; 64];
-        self.ctx.data.clear();
-        self.result = [0 as u8; 32];
+        self.ctx.hash = [1u8; 64];
+        self.ctx.N = [0u64; 8];
+        self.ctx.sigma = [0u64; 8];
+        self.ctx.data_len = 0;
+        self.ctx.blocks = 0;
+        self.ctx.absorbed = 0;
+        self.len_exceeded = false;
+        self.result = [0u8; 32];
     }
 }
 
-impl PartialEq for StreebogHasherCtx {
-    fn eq(&self, other: &StreebogHasherCtx) -> bool {
-        fn cmp_arrays(l: [u8; 64], r: [u8; 64]) -> bool {
-            for i in l.iter().zip(r.iter()) {
-                if *i.0 != *i.1 {
-                    return false;
-                }
-            }
-            return true;
-        }
-        //cmp_arrays(self.iv, other.iv) &&
-        cmp_arrays(self.hash, other.hash) && cmp_arrays(self.N, other.N)
-            && cmp_arrays(self.sigma, other.sigma) && (self.data == other.data)
+impl Default for StreebogHasher256 {
+    fn default() -> StreebogHasher256 {
+        StreebogHasher256::new()
     }
 }
-impl Eq for StreebogHasherCtx {}
 
-// Data come in Little-endian
-fn pad_data(data: Vec<u8>) -> [u8; 64] {
-    let mut padded_data = [0 as u8; 64];
-    let data_len = data.len();
-    for i in 0..data_len {
-        padded_data[i] = data[i];
-    }
-    padded_data[data_len] = 0x1;
-    padded_data
+impl StreebogParams for StreebogHasher256 {
+    const OUTPUT_SIZE: usize = 32;
 }
 
-fn streebog_update(ctx: &mut StreebogHasherCtx, data: &[u8]) -> usize {
-    let mut bytes512 = [0 as u8; 64];
-    bytes512[1] = 0x2;
-
-    ctx.data.extend_from_slice(data);
+/// With `zeroize` feature internal state and result are wiped on drop (e.g. for keyed hashing).
+#[cfg(feature = "zeroize")]
+impl Drop for StreebogHasher256 {
+    fn drop(&mut self) {
+        self.result.zeroize();
+    }
+}
 
-    let mut data_len: usize;
-    loop {
-        // Check length of data in context
-        data_len = ctx.data.len();
-        if data_len < 64 {
-            return data_len;
-        }
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for StreebogHasher256 {}
 
+/// Writing data is the same as `update`, so hasher can be used with `io::copy` and other generic
+/// code. Writing into finished hasher fails with error of kind `ErrorKind::Other` (as well as
+/// writing over limit of `with_max_len`), flushing does nothing.
+#[cfg(feature = "std")]
+impl Write for StreebogHasher256 {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.try_update(buf).map_err(io::Error::other)?;
+        Ok(buf.len())
+    }
 
-        let mut data_chunk = [0 as u8; 64];
-        for i in 0..64 {
-            data_chunk[i] = ctx.data[i];
-        }
-        ctx.hash = g_N(ctx.N, ctx.hash, data_chunk);
-        ctx.N = add_modulo512(ctx.N, bytes512);
-        ctx.sigma = add_modulo512(ctx.sigma, data_chunk);
-        ctx.data = ctx.data.split_off(64);
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
     }
 }
 
-fn streebog_finish(ctx: &mut StreebogHasherCtx, mode: StreebogHasherDigest) -> Vec<u8> {
-    let padded_data = pad_data(ctx.data.clone());
-    let data_len = ctx.data.len() as i32 * 8;
-    let mut bytes_len = [0 as u8; 64];
-    bytes_len[0] = data_len as u8 & 0xff;
-    bytes_len[1] = (data_len >> 8) as u8;
-    ctx.hash = g_N(ctx.N, ctx.hash, padded_data);
-    ctx.N = add_modulo512(ctx.N, bytes_len);
-    ctx.sigma = add_modulo512(ctx.sigma, padded_data);
-    ctx.hash = g_N([0 as u8; 64], ctx.hash, ctx.N);
-    ctx.hash = g_N([0 as u8; 64], ctx.hash, ctx.sigma);
-    let result_temp = match mode {
-        StreebogHasherDigest::StreebogHasher256 => &ctx.hash[32..64],
-        StreebogHasherDigest::StreebogHasher512 => &ctx.hash[..],
-    };
-    let mut result = Vec::new();
-    result.extend_from_slice(result_temp);
-    // Result in Little-endian cuz of internal representation of all data
-    result
-}
+impl StreebogHasher256 {
+    /// Consumes hasher, finishes hashing (if not finished yet) and returns digest.
+    pub fn finalize(mut self) -> Streebog256Digest {
+        StreebogHasher::finish(&mut self);
+        Streebog256Digest(self.result)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Consumes hasher, finishes hashing (if not finished yet) and returns result of hashing (in
+    /// big-endian byte order, as returned by `get_result`) together with total number of bytes
+    /// written since creation or last reset.
+    pub fn finalize_with_len(self) -> ([u8; 32], u64) {
+        let len = self.ctx.absorbed;
+        (self.finalize().0, len)
+    }
 
-    static data_1: &'static [u8] =
-        &[0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33,
-          0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37,
-          0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31,
-          0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35,
-          0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32];
+    /// Finishes hashing (if not finished yet), resets hasher (the same as `reset`, so it can be
+    /// reused for the next message) and returns result of hashing (in big-endian byte order, as
+    /// returned by `get_result`).
+    ///
+    /// **Note!** If limit of `with_max_len` was exceeded, hasher can't be finished and zeroes are
+    /// returned.
+    pub fn finalize_reset(&mut self) -> [u8; 32] {
+        StreebogHasher::finish(self);
+        let result = self.result;
+        StreebogHasher::reset(self);
+        result
+    }
 
-    static data_2: &'static [u8] =
-        &[0xd1, 0xe5, 0x20, 0xe2, 0xe5, 0xf2, 0xf0, 0xe8, 0x2c, 0x20, 0xd1, 0xf2, 0xf0, 0xe8,
-          0xe1, 0xee, 0xe6, 0xe8, 0x20, 0xe2, 0xed, 0xf3, 0xf6, 0xe8, 0x2c, 0x20, 0xe2, 0xe5,
+    /// Consumes hasher, finishes hashing (if not finished yet) and sends result of hashing (in
+    /// big-endian byte order, as returned by `get_result`) to channel.
+    ///
+    /// Returns error (which contains digest), if receiver is disconnected.
+    #[cfg(feature = "std")]
+    pub fn finalize_send(self, tx: Sender<[u8; 32]>) -> Result<(), SendError<[u8; 32]>> {
+        tx.send(self.finalize().0)
+    }
+
+    /// Finishes hashing (if not finished yet) and returns full 512-bit internal state, which is
+    /// truncated to obtain the digest, in big-endian byte order (so its first 32 bytes are equal
+    /// to `get_result`).
+    ///
+    /// **Note!** This is not a standard Streebog-256 digest (and not Streebog-512 digest either,
+    /// because of different IV), it is intended only for debugging and research and must not be
+    /// used where standard digest is expected.
+    pub fn raw_state(&mut self) -> [u8; 64] {
+        StreebogHasher::finish(self);
+        let mut result = [0u8; 64];
+        for i in result.iter_mut().zip(self.ctx.hash.iter().rev()) {
+            *i.0 = *i.1;
+        }
+        result
+    }
+
+    /// Returns first `bytes` bytes of result of hashing (in big-endian byte order, as returned by
+    /// `get_result`) as lowercase hex without `0x` prefix, e.g. `fingerprint(4)` gives short
+    /// 8-character identifier for display. If `bytes` is greater than digest size, the whole
+    /// digest is returned.
+    ///
+    /// **Note!** Short fingerprint is intended only for display, it is not cryptographically
+    /// meaningful: collisions of few bytes can be easily found.
+    ///
+    /// If hasher is not finished (i.e. finish is not called), it returns empty String.
+    #[cfg(feature = "alloc")]
+    pub fn fingerprint(&self, bytes: usize) -> String {
+        if self.is_finished {
+            fingerprint_hex(&self.result, bytes)
+        } else {
+            String::new()
+        }
+    }
+
+    /// Creates new hasher, which accepts no more than `max` bytes of data in total (e.g. to limit
+    /// CPU time spent on hashing untrusted input). When data exceeding limit is written, it is
+    /// not hashed and hasher stops accepting any data (`try_update` returns error) until reset.
+    ///
+    /// **Note!** Hasher, which exceeded limit, can't be finished: `finish` does nothing (and
+    /// `get_result` returns empty result), `try_finish` returns error.
+    pub fn with_max_len(max: u64) -> StreebogHasher256 {
+        let mut hasher = StreebogHasher256::new();
+        hasher.max_len = max;
+        hasher
+    }
+
+    // Hashes data, if it doesn't exceed length limit, and returns whether it was hashed
+    fn absorb(&mut self, data: &[u8]) -> bool {
+        if self.len_exceeded || data.len() as u64 > self.max_len - self.ctx.absorbed {
+            self.len_exceeded = true;
+            return false;
+        }
+        streebog_update(&mut self.ctx, data);
+        true
+    }
+
+    /// Creates new hasher with `prefix` (e.g. key) already written into it. State after
+    /// absorbing prefix is saved, so hasher can be returned to it with `reset_to_prefix` without
+    /// hashing prefix again.
+    ///
+    /// **Note!** Saved state takes additional heap allocation of about 200 bytes plus length of
+    /// unhashed tail of prefix (less than 64 bytes).
+    #[cfg(feature = "alloc")]
+    pub fn with_prefix(prefix: &[u8]) -> StreebogHasher256 {
+        let mut hasher = StreebogHasher256::new();
+        streebog_update(&mut hasher.ctx, prefix);
+        hasher.prefix_ctx = Some(Box::new(hasher.ctx.clone()));
+        hasher
+    }
+
+    /// Reset hasher to state right after absorbing prefix, passed to `with_prefix`, and mark as
+    /// not finished, so next message can be hashed with the same prefix.
+    ///
+    /// If hasher was not created with `with_prefix`, it is the same as `reset`.
+    #[cfg(feature = "alloc")]
+    pub fn reset_to_prefix(&mut self) {
+        match self.prefix_ctx {
+            Some(ref prefix_ctx) => {
+                fips_self_test();
+                self.is_finished = false;
+                self.ctx.clone_from(prefix_ctx);
+                self.len_exceeded = false;
+                self.result = [0u8; 32];
+            }
+            None => StreebogHasher::reset(self),
+        }
+    }
+
+    /// Returns independent copy of this hasher (the same as `clone`), which is the way to fork
+    /// hashing: both original and branch can be updated with different data and finished
+    /// separately, without affecting each other. It is useful for hashing many messages with common
+    /// prefix (e.g. nodes of Merkle tree).
+    pub fn branch(&self) -> Self {
+        self.clone()
+    }
+
+    /// Encodes state of this hasher (intermediate hash, counters, buffered data and result, if
+    /// finished) into versioned byte layout, so hashing can be resumed later with `restore_state`
+    /// (e.g. in another process), without `serde`.
+    ///
+    /// **Note!** Prefix of `with_prefix` and limit of `with_max_len` are not saved. Saved state
+    /// contains buffered data, i.e. the tail of the message.
+    #[cfg(feature = "alloc")]
+    pub fn save_state(&self) -> Vec<u8> {
+        let result = if self.is_finished {
+            Some(&self.result[..])
+        } else {
+            None
+        };
+        save_ctx(&self.ctx, 32, result)
+    }
+
+    /// Restores hasher from state encoded by `StreebogHasher256::save_state`.
+    ///
+    /// Returns `StreebogError::UnsupportedVersion` for unknown layout version,
+    /// `StreebogError::InvalidState` for state of other variant (or malformed one) and
+    /// `StreebogError::InvalidLength` if state is truncated or has trailing bytes.
+    pub fn restore_state(bytes: &[u8]) -> Result<StreebogHasher256, HasherError> {
+        let mut hasher = StreebogHasher256::new();
+        let (ctx, is_finished) = restore_ctx(bytes, 32, &mut hasher.result)?;
+        hasher.ctx = ctx;
+        hasher.is_finished = is_finished;
+        Ok(hasher)
+    }
+
+    /// Returns owned copy of state of this hasher, which can be moved to another thread and
+    /// finished there independently of this hasher.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot(&self) -> SendableState {
+        SendableState {
+            ctx: self.ctx.clone(),
+            mode: StreebogHasherDigest::StreebogHasher256,
+            result: if self.is_finished {
+                Some(self.get_result())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Checks whether internal state (intermediate hash, counters and buffered data) of two
+    /// hashers is the same, e.g. to verify in tests, that different sequences of updates lead to
+    /// the same state.
+    ///
+    /// **Note!** This comparison is not constant-time, so it must not be used to verify secret
+    /// data.
+    pub fn state_eq(&self, other: &Self) -> bool {
+        self.is_finished == other.is_finished && self.ctx == other.ctx
+    }
+
+    /// Writes digest of previous message into this hasher to build hash chain.
+    ///
+    /// Canonical chaining convention of this crate is `link[0] = H(message[0])` and
+    /// `link[i] = H(link[i - 1] || message[i])`, i.e. previous digest is written first (before any
+    /// other data), in big-endian byte order (as returned by `get_result`), without any separator
+    /// (digest has fixed length, so there is no ambiguity). So it is the same as
+    /// `update(previous_digest)` on a fresh hasher.
+    pub fn chain_from(&mut self, previous_digest: &[u8; 32]) {
+        StreebogHasher::update(self, previous_digest);
+    }
+
+    /// Returns number of 64-byte blocks compressed so far (i.e. number of compression function
+    /// invocations, which is proportional to CPU cost of hashing).
+    ///
+    /// Finished hasher also counts the last padded block and the two finalization compressions.
+    pub fn blocks_compressed(&self) -> u64 {
+        self.ctx.blocks
+    }
+
+    /// Writes some data into this hasher and returns number of bytes, which are buffered, but not
+    /// hashed yet (it is always less than 64, because only full 64-byte blocks are hashed before
+    /// finish).
+    ///
+    /// If hasher is finished, data is ignored and it returns 0.
+    pub fn update_reporting(&mut self, data_chunk: &[u8]) -> usize {
+        if self.is_finished {
+            0
+        } else {
+            self.absorb(data_chunk);
+            self.ctx.data_len
+        }
+    }
+
+    /// Writes some data into this hasher and returns number of 64-byte blocks, which are
+    /// compressed as result of this call (0, if data is only buffered), e.g. for progress
+    /// reporting.
+    ///
+    /// If hasher is finished, data is ignored and it returns 0.
+    pub fn update_counting(&mut self, data_chunk: &[u8]) -> u64 {
+        let blocks = self.ctx.blocks;
+        StreebogHasher::update(self, data_chunk);
+        self.ctx.blocks - blocks
+    }
+
+    /// Finishes hashing (if not finished yet) and XORs result of hashing into accumulator `acc`.
+    ///
+    /// XOR is commutative and associative, so accumulator of set of elements doesn't depend on
+    /// order, in which their digests are added, and adding the same digest twice removes it (e.g.
+    /// for set reconciliation).
+    pub fn xor_into(&mut self, acc: &mut [u8; 32]) {
+        StreebogHasher::finish(self);
+        for i in acc.iter_mut().zip(self.result.iter()) {
+            *i.0 ^= *i.1;
+        }
+    }
+
+    /// Writes data into this hasher, but no more than needed to reach `total_expected` bytes
+    /// written in total since creation or last reset (including prefix of `with_prefix`), e.g.
+    /// for length-delimited messages, which arrive by parts. Returns number of consumed bytes and
+    /// whether `total_expected` bytes are written (so hasher is ready to finish).
+    ///
+    /// If hasher is finished, data is not consumed.
+    pub fn update_until(&mut self, data: &[u8], total_expected: u64) -> (usize, bool) {
+        let remaining = total_expected.saturating_sub(self.ctx.absorbed);
+        let len = if self.is_finished {
+            0
+        } else {
+            core::cmp::min(remaining, data.len() as u64) as usize
+        };
+        if !self.absorb(&data[..len]) {
+            return (0, false);
+        }
+        (len, self.ctx.absorbed >= total_expected)
+    }
+
+    /// Returns result of hashing as hex-encoded ASCII bytes without `0x` prefix.
+    ///
+    /// If hasher is not finished (i.e. finish is not called), it returns empty Vec.
+    #[cfg(feature = "alloc")]
+    pub fn to_hex_bytes(&self, uppercase: bool) -> Vec<u8> {
+        if self.is_finished {
+            hex_bytes(&self.result, uppercase)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Returns result of hashing as lowercase hex without `0x` prefix.
+    ///
+    /// If hasher is not finished (i.e. finish is not called), it returns empty String.
+    #[cfg(feature = "alloc")]
+    pub fn to_hex(&self) -> String {
+        hex_string(&self.to_hex_bytes(false))
+    }
+
+    /// Returns result of hashing as uppercase hex without `0x` prefix.
+    ///
+    /// If hasher is not finished (i.e. finish is not called), it returns empty String.
+    #[cfg(feature = "alloc")]
+    pub fn to_hex_upper(&self) -> String {
+        hex_string(&self.to_hex_bytes(true))
+    }
+
+    /// Consumes unfinished hasher and returns data, which was written into it, but not hashed
+    /// yet (i.e. the tail of data shorter than one 64-byte block).
+    ///
+    /// **Note!** Data from already compressed blocks can't be recovered.
+    ///
+    /// If hasher is finished, it returns empty Vec.
+    #[cfg(feature = "alloc")]
+    pub fn into_pending(self) -> Vec<u8> {
+        if self.is_finished {
+            Vec::new()
+        } else {
+            self.ctx.data[..self.ctx.data_len].to_vec()
+        }
+    }
+
+    /// The same as `get_result_str`, but returns string with fixed capacity, which doesn't
+    /// require allocator.
+    #[cfg(feature = "heapless")]
+    pub fn get_result_str_heapless(&self) -> heapless::String<HEX_STR_CAPACITY> {
+        if self.is_finished {
+            hex_str_heapless(&self.result)
+        } else {
+            heapless::String::new()
+        }
+    }
+
+    /// The same as `into_pending`, but returns vector with fixed capacity, which doesn't require
+    /// allocator.
+    #[cfg(feature = "heapless")]
+    pub fn into_pending_heapless(self) -> heapless::Vec<u8, BLOCK_CAPACITY> {
+        if self.is_finished {
+            heapless::Vec::new()
+        } else {
+            heapless::Vec::from_slice(&self.ctx.data[..self.ctx.data_len]).unwrap()
+        }
+    }
+}
+
+/// Computes Streebog-512 of Streebog-512 of data (i.e. `H(H(data))`).
+///
+/// The second pass hashes digest of the first one in big-endian byte order (the same as returned
+/// by `get_result`).
+pub fn streebog512_double(data: &[u8]) -> [u8; 64] {
+    hash512(&hash512(data))
+}
+
+/// Computes Streebog-256 of Streebog-256 of data (i.e. `H(H(data))`).
+///
+/// The second pass hashes digest of the first one in big-endian byte order (the same as returned
+/// by `get_result`).
+pub fn streebog256_double(data: &[u8]) -> [u8; 32] {
+    hash256(&hash256(data))
+}
+
+/// Computes Streebog-512 of part of data in `range` (e.g. body of frame without header). Result
+/// is in big-endian byte order (as returned by `get_result`).
+///
+/// # Panics
+///
+/// Panics if `range` is out of bounds of data or its start is greater than end.
+pub fn streebog512_range(data: &[u8], range: Range<usize>) -> [u8; 64] {
+    assert!(range.start <= range.end && range.end <= data.len(),
+            "range {}..{} is out of bounds of data of length {}",
+            range.start,
+            range.end,
+            data.len());
+    hash512(&data[range])
+}
+
+/// Computes Streebog-512 of UTF-8 bytes of text.
+pub fn streebog512_text(s: &str) -> [u8; 64] {
+    hash512(s.as_bytes())
+}
+
+/// Checks, that data is valid UTF-8 text, and computes its Streebog-512 (e.g. for workflows,
+/// which must reject binary data).
+pub fn streebog512_utf8(data: &[u8]) -> Result<[u8; 64], Utf8Error> {
+    str::from_utf8(data).map(streebog512_text)
+}
+
+/// Computes Streebog-512 of content of files, read in given order, as one message (e.g. to hash
+/// whole directory as one stream). Result is in big-endian byte order (as returned by
+/// `get_result`).
+///
+/// **Note!** Content of files is simply concatenated, boundaries between files are not encoded
+/// in any way, so order of files matters and `["ab", "c"]` gives the same digest as
+/// `["a", "bc"]`. If it is important, caller should write length of each file before its content
+/// on its own.
+#[cfg(feature = "std")]
+pub fn streebog512_files<P: AsRef<Path>>(paths: &[P]) -> io::Result<[u8; 64]> {
+    let mut hasher = StreebogHasher512::new();
+    for path in paths.iter() {
+        let mut file = File::open(path)?;
+        hasher.update_from_reader(&mut file)?;
+    }
+    hasher.finish();
+    Ok(hasher.result)
+}
+
+/// Reads data from reader line by line until EOF and computes Streebog-512 of it. Result is in
+/// big-endian byte order (as returned by `get_result`).
+///
+/// Lines are hashed as raw bytes including terminators exactly as present (`\n` or `\r\n`, and
+/// the last line may have no terminator), so result is the same as of hashing all data at once.
+/// Lines don't have to be valid UTF-8.
+#[cfg(feature = "std")]
+pub fn streebog512_lines<R: BufRead>(mut reader: R) -> io::Result<[u8; 64]> {
+    let mut hasher = StreebogHasher512::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => hasher.update(&line),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    hasher.finish();
+    Ok(hasher.result)
+}
+
+/// Computes Streebog-512 of concatenation of chunks from fallible source (e.g. decoder, which
+/// can fail in the middle of stream). Stops at the first error and returns it. Result is in
+/// big-endian byte order (as returned by `get_result`).
+#[cfg(feature = "alloc")]
+pub fn streebog512_try_chunks<I, E>(chunks: I) -> Result<[u8; 64], E>
+    where I: IntoIterator<Item = Result<Vec<u8>, E>>
+{
+    let mut hasher = StreebogHasher512::new();
+    for chunk in chunks {
+        hasher.update(&chunk?);
+    }
+    hasher.finish();
+    Ok(hasher.result)
+}
+
+/// Computes Streebog-512 of concatenation of buffers (e.g. prepared for vectored write) without
+/// gathering them into one buffer. Result is in big-endian byte order (as returned by
+/// `get_result`).
+#[cfg(feature = "std")]
+pub fn streebog512_vectored(slices: &[IoSlice]) -> [u8; 64] {
+    let mut hasher = StreebogHasher512::new();
+    for slice in slices.iter() {
+        hasher.update(slice);
+    }
+    hasher.finish();
+    hasher.result
+}
+
+/// Generates mask of `out_len` bytes from `seed` with MGF1 (as defined in RFC 8017, appendix B.2.1)
+/// using Streebog-512 as underlying hash function.
+///
+/// Output is concatenation of `H(seed || C)` for 32-bit big-endian counter `C` starting from 0,
+/// truncated to `out_len` bytes, where each digest is in big-endian byte order (as returned by
+/// `get_result`).
+///
+/// # Panics
+///
+/// Panics if `out_len` is greater than `2^32 * 64` (counter overflow).
+#[cfg(feature = "alloc")]
+pub fn mgf1_streebog512(seed: &[u8], out_len: usize) -> Vec<u8> {
+    assert!(out_len as u64 <= (1u64 << 32) * 64, "MGF1 output length is too large");
+    let mut result = Vec::with_capacity(out_len);
+    let mut counter = 0u32;
+    while result.len() < out_len {
+        let len = core::cmp::min(out_len - result.len(), 64);
+        result.extend_from_slice(&mgf1_block512(seed, counter)[..len]);
+        counter = counter.wrapping_add(1);
+    }
+    result
+}
+
+// H(seed || C) for 32-bit big-endian counter C
+#[cfg(feature = "alloc")]
+fn mgf1_block512(seed: &[u8], counter: u32) -> [u8; 64] {
+    let mut hasher = StreebogHasher512::new();
+    hasher.update(seed);
+    hasher.update_u32_be(counter);
+    hasher.finish();
+    hasher.result
+}
+
+/// Computes Streebog-512 of each record, where every record is a complete message of exactly 64
+/// bytes (e.g. fixed-size database rows).
+///
+/// It is intended for hashing many independent fixed-size records with minimal per-record
+/// overhead, not for streaming. With `rayon` feature records are hashed in parallel.
+#[cfg(feature = "alloc")]
+pub fn hash_fixed_records512(records: &[[u8; 64]]) -> Vec<[u8; 64]> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        records.par_iter().map(hash_fixed_record512).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        records.iter().map(hash_fixed_record512).collect()
+    }
+}
+
+// Context on stack instead of hasher object: no prefix, length limit and result buffer
+#[cfg(feature = "alloc")]
+fn hash_fixed_record512(record: &[u8; 64]) -> [u8; 64] {
+    fips_self_test();
+    let mut ctx = StreebogHasherCtx::new(0);
+    streebog_update(&mut ctx, record);
+    reversed512(streebog_finish(&mut ctx, StreebogHasherDigest::StreebogHasher512, 0))
+}
+
+/// Computes Streebog-512 of data without any heap allocation (e.g. for targets without
+/// allocator). Result is in big-endian byte order (as returned by `get_result`).
+///
+/// Data is hashed by full 64-byte blocks directly from slice, and the last incomplete block is
+/// padded and compressed in `scratch` (on return it contains padded last block).
+pub fn streebog512_oneshot_nostd(data: &[u8], scratch: &mut [u8; 64]) -> [u8; 64] {
+    fips_self_test();
+    let mut ctx = StreebogHasherCtx::new(0);
+    let tail_start = data.len() - data.len() % 64;
+    streebog_update(&mut ctx, &data[..tail_start]);
+    let tail = &data[tail_start..];
+    *scratch = [0u8; 64];
+    scratch[..tail.len()].copy_from_slice(tail);
+    scratch[tail.len()] = 0x1;
+    reversed512(streebog_finish_padded(&mut ctx,
+                                       scratch,
+                                       tail.len(),
+                                       StreebogHasherDigest::StreebogHasher512))
+}
+
+// Digest in big-endian byte order (as returned by `get_result`) from internal representation
+fn reversed512(hash: &[u8]) -> [u8; 64] {
+    let mut result = [0u8; 64];
+    for i in result.iter_mut().zip(hash.iter().rev()) {
+        *i.0 = *i.1;
+    }
+    result
+}
+
+/// Computes secret-prefix MAC `H(key || message)` with Streebog-512. Result is in big-endian
+/// byte order (as returned by `get_result`).
+///
+/// **Note!** This construction is discouraged and provided only for legacy protocols. Key is not
+/// padded to block size, so boundary between key and message is ambiguous (`("ab", "c")` and
+/// `("a", "bc")` give the same MAC). Length extension is prevented only by Streebog finalization
+/// (compression of message length and checksum), not by construction itself, so it has no
+/// security proof as MAC (unlike HMAC) and is broken with any Merkle-Damgard hash without such
+/// finalization. Use HMAC instead.
+#[cfg(feature = "legacy-insecure-mac")]
+pub fn prefix_mac512(key: &[u8], message: &[u8]) -> [u8; 64] {
+    let mut hasher = StreebogHasher512::new();
+    hasher.update(key);
+    hasher.update(message);
+    hasher.finish();
+    hasher.result
+}
+
+/// Computes secret-prefix MAC `H(key || message)` with Streebog-256. Result is in big-endian
+/// byte order (as returned by `get_result`).
+///
+/// **Note!** This construction is discouraged, see `prefix_mac512` for details. Use HMAC instead.
+#[cfg(feature = "legacy-insecure-mac")]
+pub fn prefix_mac256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut hasher = StreebogHasher256::new();
+    hasher.update(key);
+    hasher.update(message);
+    hasher.finish();
+    hasher.result
+}
+
+/// Runs compression function over whole blocks with IV of Streebog-512 and block counter, as in
+/// the update stage of algorithm, and returns resulting chaining value in big-endian byte order
+/// (the same order as `get_result`). Blocks are in the same byte order as data passed to
+/// `update`.
+///
+/// **Note!** Output is **not** a Streebog digest: padding, compression of length and checksum
+/// are skipped entirely. It is intended only for comparing with intermediate values of reference
+/// implementations and must never be used in place of digest.
+#[cfg(feature = "low-level")]
+pub fn compress_only(blocks: &[[u8; 64]]) -> [u8; 64] {
+    let mut words512 = [0u64; 8];
+    words512[0] = 512;
+    let mut hash = [0u8; 64];
+    let mut N = [0u64; 8];
+    for block in blocks.iter() {
+        hash = g_N(words_to_bytes(N), hash, *block);
+        N = add_modulo512_words(N, words512);
+    }
+    let mut result = [0u8; 64];
+    for i in result.iter_mut().zip(hash.iter().rev()) {
+        *i.0 = *i.1;
+    }
+    result
+}
+
+/// Computes commitment `H(nonce || message)` with Streebog-512 (nonce is always written first).
+/// Result is in big-endian byte order (as returned by `get_result`).
+///
+/// Commitment is binding due to collision resistance of hash, but it is hiding only if nonce is
+/// random and high-entropy (at least 32 bytes from secure random generator) and is kept secret
+/// until opening. With guessable nonce, message can be found by brute force.
+///
+/// **Note!** Nonce is not framed, so the same commitment can be opened as `(nonce + "a", "b")`
+/// and `(nonce, "ab")`. Use fixed nonce length to avoid it.
+pub fn commit512(nonce: &[u8], message: &[u8]) -> [u8; 64] {
+    let mut hasher = StreebogHasher512::new();
+    hasher.update(nonce);
+    hasher.update(message);
+    hasher.finish();
+    hasher.result
+}
+
+/// Checks in constant time, that commitment is opened by `nonce` and `message` (see `commit512`).
+pub fn verify_commitment512(commitment: &[u8; 64], nonce: &[u8], message: &[u8]) -> bool {
+    ct_eq(&commit512(nonce, message), commitment)
+}
+
+/// Computes Streebog-512 of data and compares it with `expected` in constant time.
+///
+/// Returns digest (which is equal to `expected`) on match and `None` otherwise.
+pub fn hash_and_verify512(data: &[u8], expected: &[u8; 64]) -> Option<[u8; 64]> {
+    let result = hash512(data);
+    if ct_eq(&result, expected) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Computes Streebog-256 of data and compares it with `expected` in constant time.
+///
+/// Returns digest (which is equal to `expected`) on match and `None` otherwise.
+pub fn hash_and_verify256(data: &[u8], expected: &[u8; 32]) -> Option<[u8; 32]> {
+    let result = hash256(data);
+    if ct_eq(&result, expected) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Owned copy of state of a hasher (see `snapshot`), which is `Send + 'static`, so finalization can
+/// be handed off to another thread without sharing the live hasher.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use streebog_hash::*;
+/// let mut hasher = StreebogHasher512::new();
+/// hasher.update(&[0xfcu8; 128]);
+/// let state = hasher.snapshot();
+/// let result = thread::spawn(move || state.finish()).join().unwrap();
+/// ```
+#[cfg(feature = "alloc")]
+pub struct SendableState {
+    ctx: StreebogHasherCtx,
+    mode: StreebogHasherDigest,
+    result: Option<Box<[u8]>>,
+}
+
+#[cfg(feature = "alloc")]
+impl SendableState {
+    /// Completes hashing of data written before snapshot and returns result of hashing (the same
+    /// as `get_result` of finished hasher).
+    pub fn finish(mut self) -> Box<[u8]> {
+        match self.result {
+            Some(result) => result,
+            None => {
+                let mut result = streebog_finish(&mut self.ctx, self.mode, 0).to_vec();
+                result.reverse();
+                result.into_boxed_slice()
+            }
+        }
+    }
+}
+
+// Layout of saved state: version, digest size of variant, flags (bit 0 is set for finished
+// hasher), hash, N, sigma (words are little-endian), blocks, absorbed, length of buffered data,
+// buffered data and result of finished hasher
+const STATE_VERSION: u8 = 1;
+const STATE_HEADER_LEN: usize = 3 + 64 * 3 + 8 * 2 + 1;
+
+#[cfg(feature = "alloc")]
+fn save_ctx(ctx: &StreebogHasherCtx, size: u8, result: Option<&[u8]>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(STATE_HEADER_LEN + ctx.data_len + size as usize);
+    bytes.push(STATE_VERSION);
+    bytes.push(size);
+    bytes.push(result.is_some() as u8);
+    bytes.extend_from_slice(&ctx.hash);
+    bytes.extend_from_slice(&words_to_bytes(ctx.N));
+    bytes.extend_from_slice(&words_to_bytes(ctx.sigma));
+    bytes.extend_from_slice(&ctx.blocks.to_le_bytes());
+    bytes.extend_from_slice(&ctx.absorbed.to_le_bytes());
+    bytes.push(ctx.data_len as u8);
+    bytes.extend_from_slice(&ctx.data[..ctx.data_len]);
+    if let Some(result) = result {
+        bytes.extend_from_slice(result);
+    }
+    bytes
+}
+
+// Returns context and whether hasher is finished (then its result is written into `result`)
+fn restore_ctx(bytes: &[u8], size: u8, result: &mut [u8])
+               -> Result<(StreebogHasherCtx, bool), StreebogError> {
+    if bytes.len() < STATE_HEADER_LEN {
+        return Err(StreebogError::InvalidLength {
+            expected: STATE_HEADER_LEN,
+            actual: bytes.len(),
+        });
+    }
+    if bytes[0] != STATE_VERSION {
+        return Err(StreebogError::UnsupportedVersion);
+    }
+    let data_len = bytes[STATE_HEADER_LEN - 1] as usize;
+    if bytes[1] != size || bytes[2] > 1 || data_len >= 64 {
+        return Err(StreebogError::InvalidState);
+    }
+    let is_finished = bytes[2] == 1;
+    let expected = STATE_HEADER_LEN + data_len + if is_finished { result.len() } else { 0 };
+    if bytes.len() != expected {
+        return Err(StreebogError::InvalidLength {
+            expected,
+            actual: bytes.len(),
+        });
+    }
+
+    fn block(bytes: &[u8]) -> [u8; 64] {
+        let mut block = [0u8; 64];
+        block.copy_from_slice(&bytes[..64]);
+        block
+    }
+    fn u64_le(bytes: &[u8]) -> u64 {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&bytes[..8]);
+        u64::from_le_bytes(word)
+    }
+    let mut ctx = StreebogHasherCtx {
+        hash: block(&bytes[3..]),
+        N: bytes_to_words(block(&bytes[67..])),
+        sigma: bytes_to_words(block(&bytes[131..])),
+        data: [0u8; 64],
+        data_len,
+        blocks: u64_le(&bytes[195..]),
+        absorbed: u64_le(&bytes[203..]),
+    };
+    ctx.data[..data_len].copy_from_slice(&bytes[STATE_HEADER_LEN..STATE_HEADER_LEN + data_len]);
+    if is_finished {
+        result.copy_from_slice(&bytes[STATE_HEADER_LEN + data_len..]);
+    }
+    Ok((ctx, is_finished))
+}
+
+impl StreebogHasherCtx {
+    // Initial state with IV of all bytes equal to `iv` (0 for Streebog-512, 1 for Streebog-256)
+    fn new(iv: u8) -> StreebogHasherCtx {
+        StreebogHasherCtx {
+            hash: [iv; 64],
+            N: [0u64; 8],
+            sigma: [0u64; 8],
+            data: [0u8; 64],
+            data_len: 0,
+            blocks: 0,
+            absorbed: 0,
+        }
+    }
+}
+
+impl PartialEq for StreebogHasherCtx {
+    fn eq(&self, other: &StreebogHasherCtx) -> bool {
+        fn cmp_arrays(l: [u8; 64], r: [u8; 64]) -> bool {
+            for i in l.iter().zip(r.iter()) {
+                if *i.0 != *i.1 {
+                    return false;
+                }
+            }
+            true
+        }
+        //cmp_arrays(self.iv, other.iv) &&
+        cmp_arrays(self.hash, other.hash) && self.N == other.N && self.sigma == other.sigma
+            && self.data[..self.data_len] == other.data[..other.data_len]
+    }
+}
+impl Eq for StreebogHasherCtx {}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for StreebogHasherCtx {
+    fn zeroize(&mut self) {
+        self.hash.zeroize();
+        self.N.zeroize();
+        self.sigma.zeroize();
+        self.data.zeroize();
+        self.data_len.zeroize();
+        self.blocks.zeroize();
+        self.absorbed.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for StreebogHasherCtx {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for StreebogHasherCtx {}
+
+#[cfg(feature = "fips")]
+fn fips_self_test() {
+    assert!(kat::check_transformations(),
+            "Streebog self-test failed: core transformations or their tables are corrupted");
+}
+
+#[cfg(not(feature = "fips"))]
+#[inline(always)]
+fn fips_self_test() {}
+
+/// Computes Streebog-512 of data in one call. Result is in big-endian byte order (as returned by
+/// `get_result`).
+///
+/// # Examples
+///
+/// ```
+/// use streebog_hash::hash512;
+/// let result = hash512(b"some data");
+/// ```
+pub fn hash512(data: &[u8]) -> [u8; 64] {
+    let mut hasher = StreebogHasher512::new();
+    hasher.update(data);
+    hasher.finish();
+    hasher.result
+}
+
+/// Computes Streebog-256 of data in one call. Result is in big-endian byte order (as returned by
+/// `get_result`).
+///
+/// # Examples
+///
+/// ```
+/// use streebog_hash::hash256;
+/// let result = hash256(b"some data");
+/// ```
+pub fn hash256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = StreebogHasher256::new();
+    hasher.update(data);
+    hasher.finish();
+    hasher.result
+}
+
+/// Reads all data from reader (until EOF) and computes Streebog-512 of it. Result is in
+/// big-endian byte order (as returned by `get_result`).
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use std::fs::File;
+/// use streebog_hash::hash512_reader;
+/// let result = hash512_reader(&mut File::open("file.bin")?)?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn hash512_reader<R: Read>(reader: &mut R) -> io::Result<[u8; 64]> {
+    let mut hasher = StreebogHasher512::new();
+    hasher.update_from_reader(reader)?;
+    hasher.finish();
+    Ok(hasher.result)
+}
+
+/// Reads all data from reader (until EOF) and computes Streebog-256 of it. Result is in
+/// big-endian byte order (as returned by `get_result`).
+#[cfg(feature = "std")]
+pub fn hash256_reader<R: Read>(reader: &mut R) -> io::Result<[u8; 32]> {
+    let mut hasher = StreebogHasher256::new();
+    hasher.update_from_reader(reader)?;
+    hasher.finish();
+    Ok(hasher.result)
+}
+
+/// Compares two byte slices (e.g. computed and expected MAC) in constant time.
+///
+/// Differences are accumulated over the whole slices, so time doesn't depend on position of first
+/// mismatched byte. Slices of different length are never equal, but the common prefix is still
+/// scanned; only the length of shorter slice is revealed by timing.
+pub fn ct_eq(l: &[u8], r: &[u8]) -> bool {
+    let mut diff = (l.len() != r.len()) as u8;
+    for i in l.iter().zip(r.iter()) {
+        diff |= *i.0 ^ *i.1;
+    }
+    diff == 0
+}
+
+// Hex digits are ASCII, so they are always valid UTF-8
+#[cfg(feature = "alloc")]
+fn hex_string(hex: &[u8]) -> String {
+    hex.iter().map(|i| *i as char).collect()
+}
+
+#[cfg(feature = "alloc")]
+fn hex_bytes(data: &[u8], uppercase: bool) -> Vec<u8> {
+    let digits = if uppercase {
+        b"0123456789ABCDEF"
+    } else {
+        b"0123456789abcdef"
+    };
+    let mut result = Vec::with_capacity(data.len() * 2);
+    for i in data.iter() {
+        result.push(digits[(*i >> 4) as usize]);
+        result.push(digits[(*i & 0xf) as usize]);
+    }
+    result
+}
+
+// Parses hex string (both cases, without prefix), returns None if it is not valid hex
+#[cfg(feature = "std")]
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    fn digit(c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - b'0'),
+            b'a'..=b'f' => Some(c - b'a' + 10),
+            b'A'..=b'F' => Some(c - b'A' + 10),
+            _ => None,
+        }
+    }
+    let pairs = s.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    let mut result = Vec::with_capacity(s.len() / 2);
+    for pair in pairs {
+        result.push((digit(pair[0])? << 4) | digit(pair[1])?);
+    }
+    Some(result)
+}
+
+#[cfg(feature = "alloc")]
+fn fingerprint_hex(data: &[u8], bytes: usize) -> String {
+    let len = core::cmp::min(bytes, data.len());
+    String::from_utf8(hex_bytes(&data[..len], false)).unwrap()
+}
+
+/// Capacity of buffer for data, which is not hashed yet (one 64-byte block).
+#[cfg(feature = "heapless")]
+pub const BLOCK_CAPACITY: usize = 64;
+/// Capacity of string representation of the longest (512 bit) digest: `0x` prefix and 2 hex
+/// digits per byte.
+#[cfg(feature = "heapless")]
+pub const HEX_STR_CAPACITY: usize = 2 + 2 * 64;
+
+#[cfg(feature = "heapless")]
+fn hex_str_heapless(data: &[u8]) -> heapless::String<HEX_STR_CAPACITY> {
+    let digits = b"0123456789abcdef";
+    let mut result = heapless::String::new();
+    result.push_str("0x").unwrap();
+    for i in data.iter() {
+        result.push(digits[(*i >> 4) as usize] as char).unwrap();
+        result.push(digits[(*i & 0xf) as usize] as char).unwrap();
+    }
+    result
+}
+
+// Data come in Little-endian
+fn pad_data(data: &[u8]) -> [u8; 64] {
+    let mut padded_data = [0u8; 64];
+    let data_len = data.len();
+    padded_data[..data_len].copy_from_slice(&data[..data_len]);
+    padded_data[data_len] = 0x1;
+    padded_data
+}
+
+fn streebog_update(ctx: &mut StreebogHasherCtx, data: &[u8]) -> usize {
+    let mut words512 = [0u64; 8];
+    words512[0] = 512;
+
+    ctx.absorbed += data.len() as u64;
+    // Fast path for tiny writes, which don't fill staging buffer
+    if data.len() < 64 - ctx.data_len {
+        ctx.data[ctx.data_len..ctx.data_len + data.len()].copy_from_slice(data);
+        ctx.data_len += data.len();
+        return ctx.data_len;
+    }
+    let mut data = data;
+    while !data.is_empty() {
+        // Fill staging buffer and compress it only when it is full, so tiny writes cost only copy
+        let len = core::cmp::min(64 - ctx.data_len, data.len());
+        ctx.data[ctx.data_len..ctx.data_len + len].copy_from_slice(&data[..len]);
+        ctx.data_len += len;
+        data = &data[len..];
+        if ctx.data_len < 64 {
+            break;
+        }
+
+        let data_chunk = ctx.data;
+        ctx.hash = g_N(words_to_bytes(ctx.N), ctx.hash, data_chunk);
+        ctx.N = add_modulo512_words(ctx.N, words512);
+        ctx.sigma = add_modulo512_words(ctx.sigma, bytes_to_words(data_chunk));
+        ctx.data_len = 0;
+        ctx.blocks += 1;
+    }
+    ctx.data_len
+}
+
+// Non-zero `tag` is XORed into the most significant byte of padded block (see `finalize_tagged`)
+fn streebog_finish(ctx: &mut StreebogHasherCtx, mode: StreebogHasherDigest, tag: u8) -> &[u8] {
+    let mut padded_data = pad_data(&ctx.data[..ctx.data_len]);
+    padded_data[63] ^= tag;
+    let data_len = ctx.data_len;
+    streebog_finish_padded(ctx, &padded_data, data_len, mode)
+}
+
+// Finishes hashing with already padded last block, which contains `data_len` bytes of data (staging
+// buffer of context is ignored)
+fn streebog_finish_padded<'a>(ctx: &'a mut StreebogHasherCtx, padded_data: &[u8; 64],
+                              data_len: usize, mode: StreebogHasherDigest)
+                              -> &'a [u8] {
+    // Length of the last block in bits as 512-bit little-endian number (it is less than 512, so
+    // only the lowest word is non-zero)
+    debug_assert!(data_len < 64);
+    let mut words_len = [0u64; 8];
+    words_len[0] = data_len as u64 * 8;
+    ctx.hash = g_N(words_to_bytes(ctx.N), ctx.hash, *padded_data);
+    ctx.N = add_modulo512_words(ctx.N, words_len);
+    ctx.sigma = add_modulo512_words(ctx.sigma, bytes_to_words(*padded_data));
+    ctx.hash = g_N([0u8; 64], ctx.hash, words_to_bytes(ctx.N));
+    ctx.hash = g_N([0u8; 64], ctx.hash, words_to_bytes(ctx.sigma));
+    ctx.blocks += 3;
+    // Result in Little-endian cuz of internal representation of all data
+    match mode {
+        StreebogHasherDigest::StreebogHasher256 => &ctx.hash[32..64],
+        StreebogHasherDigest::StreebogHasher512 => &ctx.hash[..],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static data_1: &[u8] =
+        &[0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33,
+          0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37,
+          0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31,
+          0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35,
+          0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32];
+
+    static data_2: &[u8] =
+        &[0xd1, 0xe5, 0x20, 0xe2, 0xe5, 0xf2, 0xf0, 0xe8, 0x2c, 0x20, 0xd1, 0xf2, 0xf0, 0xe8,
+          0xe1, 0xee, 0xe6, 0xe8, 0x20, 0xe2, 0xed, 0xf3, 0xf6, 0xe8, 0x2c, 0x20, 0xe2, 0xe5,
           0xfe, 0xf2, 0xfa, 0x20, 0xf1, 0x20, 0xec, 0xee, 0xf0, 0xff, 0x20, 0xf1, 0xf2, 0xf0,
           0xe5, 0xeb, 0xe0, 0xec, 0xe8, 0x20, 0xed, 0xe0, 0x20, 0xf5, 0xf0, 0xe0, 0xe1, 0xf0,
           0xfb, 0xff, 0x20, 0xef, 0xeb, 0xfa, 0xea, 0xfb, 0x20, 0xc8, 0xe3, 0xee, 0xf0, 0xe5,
           0xe2, 0xfb];
 
-    static data_2_part_1: &'static [u8] =
-        &[0xd1, 0xe5, 0x20, 0xe2, 0xe5, 0xf2, 0xf0, 0xe8, 0x2c, 0x20, 0xd1, 0xf2, 0xf0, 0xe8,
-          0xe1, 0xee, 0xe6, 0xe8, 0x20, 0xe2, 0xed, 0xf3, 0xf6, 0xe8, 0x2c, 0x20, 0xe2, 0xe5,
-          0xfe, 0xf2, 0xfa, 0x20, 0xf1, 0x20, 0xec, 0xee, 0xf0];
+    static data_2_part_1: &[u8] =
+        &[0xd1, 0xe5, 0x20, 0xe2, 0xe5, 0xf2, 0xf0, 0xe8, 0x2c, 0x20, 0xd1, 0xf2, 0xf0, 0xe8,
+          0xe1, 0xee, 0xe6, 0xe8, 0x20, 0xe2, 0xed, 0xf3, 0xf6, 0xe8, 0x2c, 0x20, 0xe2, 0xe5,
+          0xfe, 0xf2, 0xfa, 0x20, 0xf1, 0x20, 0xec, 0xee, 0xf0];
+
+    static data_2_part_2: &[u8] = &[0xff, 0x20, 0xf1, 0xf2, 0xf0, 0xe5, 0xeb, 0xe0, 0xec,
+                                    0xe8, 0x20, 0xed, 0xe0, 0x20, 0xf5, 0xf0, 0xe0, 0xe1,
+                                    0xf0, 0xfb, 0xff, 0x20, 0xef, 0xeb, 0xfa, 0xea, 0xfb,
+                                    0x20, 0xc8, 0xe3, 0xee, 0xf0, 0xe5, 0xe2, 0xfb];
+
+    #[test]
+    #[should_panic]
+    fn test_streebog_update_1() {
+        let mut data = Vec::new();
+        data.extend_from_slice(data_1);
+        let should_be = [0xfd, 0x10, 0x2c, 0xf8, 0x81, 0x2c, 0xcb, 0x11, 0x91, 0xea, 0x34, 0xaf,
+                         0x21, 0x39, 0x4f, 0x38, 0x17, 0xa8, 0x66, 0x41, 0x44, 0x5a, 0xa9, 0xa6,
+                         0x26, 0x48, 0x8a, 0xdb, 0x33, 0x73, 0x8e, 0xbd, 0x27, 0x54, 0xf6, 0x90,
+                         0x8c, 0xbb, 0xba, 0xc5, 0xd3, 0xed, 0x0f, 0x52, 0x2c, 0x50, 0x81, 0x5c,
+                         0x95, 0x41, 0x35, 0x79, 0x3f, 0xb1, 0xf5, 0xd9, 0x05, 0xfe, 0xe4, 0x73,
+                         0x6b, 0x3b, 0xda, 0xe2];
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(&super::pad_data(&data)[..]);
+        assert_eq!(hasher.ctx.data_len, 0);
+        assert_eq!(&hasher.ctx.hash[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_streebog512_final_1() {
+        let mut should_be = [0x1b, 0x54, 0xd0, 0x1a, 0x4a, 0xf5, 0xb9, 0xd5, 0xcc, 0x3d, 0x86, 0xd6,
+                             0x8d, 0x28, 0x54, 0x62, 0xb1, 0x9a, 0xbc, 0x24, 0x75, 0x22, 0x2f, 0x35,
+                             0xc0, 0x85, 0x12, 0x2b, 0xe4, 0xba, 0x1f, 0xfa, 0x00, 0xad, 0x30, 0xf8,
+                             0x76, 0x7b, 0x3a, 0x82, 0x38, 0x4c, 0x65, 0x74, 0xf0, 0x24, 0xc3, 0x11,
+                             0xe2, 0xa4, 0x81, 0x33, 0x2b, 0x08, 0xef, 0x7f, 0x41, 0x79, 0x78, 0x91,
+                             0xc1, 0x64, 0x6f, 0x48];
+        // reverse order cuz output should be in big-endian
+        should_be.reverse();
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_1);
+        hasher.finish();
+        let result = hasher.get_result();
+        assert_eq!(&result[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_streebog256_final_1() {
+        let mut should_be = [0x9d, 0x15, 0x1e, 0xef, 0xd8, 0x59, 0x0b, 0x89, 0xda, 0xa6, 0xba, 0x6c,
+                             0xb7, 0x4a, 0xf9, 0x27, 0x5d, 0xd0, 0x51, 0x02, 0x6b, 0xb1, 0x49, 0xa4,
+                             0x52, 0xfd, 0x84, 0xe5, 0xe5, 0x7b, 0x55, 0x00];
+        // reverse order cuz output should be in big-endian
+        should_be.reverse();
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
+        hasher.finish();
+        let result = hasher.get_result();
+        assert_eq!(&result[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_streebog512_final_2() {
+        let mut should_be = [0x1e, 0x88, 0xe6, 0x22, 0x26, 0xbf, 0xca, 0x6f, 0x99, 0x94, 0xf1, 0xf2,
+                             0xd5, 0x15, 0x69, 0xe0, 0xda, 0xf8, 0x47, 0x5a, 0x3b, 0x0f, 0xe6, 0x1a,
+                             0x53, 0x00, 0xee, 0xe4, 0x6d, 0x96, 0x13, 0x76, 0x03, 0x5f, 0xe8, 0x35,
+                             0x49, 0xad, 0xa2, 0xb8, 0x62, 0x0f, 0xcd, 0x7c, 0x49, 0x6c, 0xe5, 0xb3,
+                             0x3f, 0x0c, 0xb9, 0xdd, 0xdc, 0x2b, 0x64, 0x60, 0x14, 0x3b, 0x03, 0xda,
+                             0xba, 0xc9, 0xfb, 0x28];
+        should_be.reverse();
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_2);
+        hasher.finish();
+        let result = hasher.get_result();
+        assert_eq!(&result[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_streebog256_final_2() {
+        let mut should_be = [0x9d, 0xd2, 0xfe, 0x4e, 0x90, 0x40, 0x9e, 0x5d, 0xa8, 0x7f, 0x53, 0x97,
+                             0x6d, 0x74, 0x05, 0xb0, 0xc0, 0xca, 0xc6, 0x28, 0xfc, 0x66, 0x9a, 0x74,
+                             0x1d, 0x50, 0x06, 0x3c, 0x55, 0x7e, 0x8f, 0x50];
+        should_be.reverse();
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2);
+        hasher.finish();
+        let result = hasher.get_result();
+        assert_eq!(&result[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_streebog_with_multiple_parts_of_data() {
+        let mut should_be = [0x1e, 0x88, 0xe6, 0x22, 0x26, 0xbf, 0xca, 0x6f, 0x99, 0x94, 0xf1, 0xf2,
+                             0xd5, 0x15, 0x69, 0xe0, 0xda, 0xf8, 0x47, 0x5a, 0x3b, 0x0f, 0xe6, 0x1a,
+                             0x53, 0x00, 0xee, 0xe4, 0x6d, 0x96, 0x13, 0x76, 0x03, 0x5f, 0xe8, 0x35,
+                             0x49, 0xad, 0xa2, 0xb8, 0x62, 0x0f, 0xcd, 0x7c, 0x49, 0x6c, 0xe5, 0xb3,
+                             0x3f, 0x0c, 0xb9, 0xdd, 0xdc, 0x2b, 0x64, 0x60, 0x14, 0x3b, 0x03, 0xda,
+                             0xba, 0xc9, 0xfb, 0x28];
+        should_be.reverse();
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_2_part_1);
+        hasher.update(data_2_part_2);
+        hasher.finish();
+        let result = hasher.get_result();
+        assert_eq!(&result[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_with() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.with(data_2_part_1).with(data_2_part_2).finish();
+        assert_eq!(hasher.result, hash512(data_2));
+
+        let mut chained = StreebogHasher256::new();
+        chained.with(data_2_part_1).with(b"").with(data_2_part_2).finish();
+        assert_eq!(chained.result, hash256(data_2));
+    }
+
+    #[test]
+    fn test_pad_data() {
+        let mut data = Vec::new();
+        data.extend_from_slice(data_1);
+        let should_be: [u8; 64] =
+            [0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33,
+             0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37,
+             0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31,
+             0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35,
+             0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x01];
+        assert_eq!(&super::pad_data(&data)[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_streebog_counter_crosses_64_bit_boundary() {
+        // Fast-forward N to 2^64 - 512 bits, so the next full block carries into the 9th byte
+        let mut hasher = StreebogHasher512::new();
+        hasher.ctx.N[0] = 0xffff_ffff_ffff_fe00;
+        hasher.update(&[0x30u8; 64]);
+        let mut should_be = [0u8; 64];
+        should_be[8] = 0x01;
+        assert_eq!(&words_to_bytes(hasher.ctx.N)[..], &should_be[..]);
+
+        // Same for the length of the last partial block added in finish
+        let mut hasher = StreebogHasher512::new();
+        hasher.ctx.N[0] = 0xffff_ffff_ffff_fff8;
+        hasher.update(&[0x30u8; 1]);
+        hasher.finish();
+        assert_eq!(&words_to_bytes(hasher.ctx.N)[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_into_pending() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_2);
+        assert_eq!(hasher.into_pending(), &data_2[64..]);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2_part_1);
+        assert_eq!(hasher.into_pending(), data_2_part_1);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2_part_1);
+        hasher.finish();
+        assert_eq!(hasher.into_pending(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_to_hex_bytes() {
+        let should_be_512: &[u8] = b"486f64c1917879417fef082b3381a4e211c324f074654c38823a7b76f830ad00\
+                                     fa1fbae42b1285c0352f227524bc9ab16254288dd6863dccd5b9f54a1ad0541b";
+        let should_be_256: &[u8] = b"00557be5e584fd52a449b16b0251d05d27f94ab76cbaa6da890b59d8ef1e159d";
+        let mut hasher = StreebogHasher512::new();
+        assert_eq!(hasher.to_hex_bytes(false), Vec::<u8>::new());
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(&hasher.to_hex_bytes(false)[..], should_be_512);
+        assert_eq!(hasher.to_hex_bytes(true), should_be_512.to_ascii_uppercase());
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(&hasher.to_hex_bytes(false)[..], should_be_256);
+        assert_eq!(hasher.to_hex_bytes(true), should_be_256.to_ascii_uppercase());
+    }
+
+    #[test]
+    fn test_update_reporting() {
+        let mut hasher = StreebogHasher512::new();
+        assert_eq!(hasher.update_reporting(data_2_part_1), 37);
+        assert_eq!(hasher.update_reporting(data_2_part_2), 8);
+        hasher.finish();
+        assert_eq!(hasher.update_reporting(data_2_part_1), 0);
+        let mut hasher = StreebogHasher256::new();
+        assert_eq!(hasher.update_reporting(&data_2[..64]), 0);
+    }
+
+    #[test]
+    fn test_blocks_compressed() {
+        let data = [0xfcu8; 200];
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(&data);
+        assert_eq!(hasher.blocks_compressed(), 3);
+        hasher.finish();
+        assert_eq!(hasher.blocks_compressed(), 6);
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(&data);
+        hasher.finish();
+        assert_eq!(hasher.blocks_compressed(), 6);
+        hasher.reset();
+        assert_eq!(hasher.blocks_compressed(), 0);
+    }
+
+    #[test]
+    fn test_streebog_double() {
+        let should_be = [0xdd, 0xb1, 0x97, 0x11, 0xb4, 0xf2, 0x18, 0xc1, 0x1b, 0x46, 0xab, 0x30,
+                         0x0c, 0x71, 0xd4, 0xd4, 0x26, 0x1e, 0xb4, 0xe1, 0x15, 0xb4, 0x4a, 0xec,
+                         0x14, 0x72, 0xdb, 0x85, 0x31, 0x17, 0x8e, 0x3a, 0x21, 0x6b, 0x83, 0xea,
+                         0x25, 0xd3, 0x8a, 0x2d, 0x66, 0xa2, 0xe4, 0xc3, 0x8f, 0x6d, 0xe8, 0x17,
+                         0x6d, 0x69, 0x21, 0xc2, 0xda, 0x99, 0xf4, 0x5e, 0xab, 0x92, 0x5f, 0x6c,
+                         0xea, 0x7d, 0x5e, 0xb8];
+        assert_eq!(&streebog512_double(data_1)[..], &should_be[..]);
+        let should_be = [0xe8, 0x64, 0x2b, 0x18, 0xa6, 0xf6, 0x76, 0x77, 0x04, 0xdc, 0xe9, 0x33,
+                         0x3f, 0x81, 0x00, 0x06, 0xc3, 0x2a, 0x60, 0xc7, 0x2c, 0xb4, 0x21, 0x49,
+                         0x2a, 0xb7, 0xb4, 0x94, 0xa8, 0xcf, 0x93, 0xb5];
+        assert_eq!(&streebog256_double(data_1)[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_chain_from() {
+        fn build_chain(messages: &[&[u8]]) -> Vec<[u8; 64]> {
+            let mut chain: Vec<[u8; 64]> = Vec::new();
+            for message in messages {
+                let mut hasher = StreebogHasher512::new();
+                if let Some(previous_digest) = chain.last() {
+                    hasher.chain_from(previous_digest);
+                }
+                hasher.update(message);
+                hasher.finish();
+                chain.push(hasher.result);
+            }
+            chain
+        }
+        let messages = [data_1, data_2_part_1, data_2_part_2];
+        let chain = build_chain(&messages);
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain, build_chain(&messages));
+
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(&chain[1]);
+        hasher.update(data_2_part_2);
+        hasher.finish();
+        assert_eq!(&hasher.get_result()[..], &chain[2][..]);
+
+        let mut digest = [0u8; 32];
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
+        hasher.finish();
+        digest.copy_from_slice(&hasher.get_result());
+        let mut hasher_chained = StreebogHasher256::new();
+        hasher_chained.chain_from(&digest);
+        hasher_chained.update(data_2);
+        hasher_chained.finish();
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(&digest);
+        hasher.update(data_2);
+        hasher.finish();
+        assert_eq!(hasher_chained.get_result(), hasher.get_result());
+    }
+
+    #[test]
+    fn test_mgf1_streebog512() {
+        let should_be = [0x82, 0xa8, 0xac, 0x10, 0xfc, 0xe2, 0x53, 0x9b, 0xc4, 0x6a, 0x85, 0xe6,
+                         0xca, 0x6d, 0x16, 0xac, 0xf9, 0xa0, 0xcc, 0x43, 0x78, 0xdc, 0xf8, 0x33,
+                         0x7a, 0x1e, 0x71, 0x66, 0xe6, 0x72, 0x8b, 0xde, 0xc6, 0x2b, 0x59, 0x5d,
+                         0x5d, 0x0c, 0x76, 0xbc, 0x0c, 0x5d, 0x4b, 0x8f, 0x2b, 0x0c, 0xba, 0x80,
+                         0x65, 0x18, 0x07, 0x88, 0x79, 0x4b, 0xb9, 0xdb, 0x72, 0x46, 0xe1, 0x34,
+                         0xb3, 0x81, 0x25, 0xc6, 0xab, 0x27, 0xe4, 0x95, 0x91, 0xc7, 0x66, 0x14,
+                         0x7c, 0x60, 0x68, 0xd8, 0x53, 0xa5, 0x38, 0x11, 0xff, 0x3a, 0x38, 0x82,
+                         0xbd, 0x16, 0x1f, 0xda, 0xee, 0xfc, 0xfa, 0x09, 0xb7, 0x15, 0xe8, 0xea,
+                         0x15, 0xb2, 0x3b, 0x93];
+        assert_eq!(&mgf1_streebog512(data_1, 100)[..], &should_be[..]);
+        assert_eq!(&mgf1_streebog512(data_1, 64)[..], &should_be[..64]);
+        assert_eq!(&mgf1_streebog512(data_1, 1)[..], &should_be[..1]);
+        assert_eq!(mgf1_streebog512(data_1, 0), Vec::<u8>::new());
+        assert_eq!(mgf1_streebog512(data_1, 200).len(), 200);
+    }
+
+    #[test]
+    fn test_state_eq() {
+        let mut hasher_1 = StreebogHasher512::new();
+        let mut hasher_2 = StreebogHasher512::new();
+        assert!(hasher_1.state_eq(&hasher_2));
+        hasher_1.update(data_2_part_1);
+        assert!(!hasher_1.state_eq(&hasher_2));
+        hasher_1.update(data_2_part_2);
+        hasher_2.update(data_2);
+        assert!(hasher_1.state_eq(&hasher_2));
+        hasher_1.finish();
+        assert!(!hasher_1.state_eq(&hasher_2));
+
+        let mut hasher_1 = StreebogHasher256::new();
+        let mut hasher_2 = StreebogHasher256::new();
+        hasher_1.update(&data_2[..10]);
+        hasher_1.update(&data_2[10..]);
+        hasher_2.update(data_2);
+        assert!(hasher_1.state_eq(&hasher_2));
+    }
+
+    #[test]
+    fn test_update_cow() {
+        let mut hasher_1 = StreebogHasher512::new();
+        hasher_1.update_cow(Cow::Borrowed(data_2_part_1));
+        hasher_1.update_cow(Cow::Owned(data_2_part_2.to_vec()));
+        let mut hasher_2 = StreebogHasher512::new();
+        hasher_2.update(data_2);
+        assert!(hasher_1.state_eq(&hasher_2));
+    }
+
+    #[test]
+    fn test_streebog_text() {
+        let text = "012345678901234567890123456789012345678901234567890123456789012";
+        let mut hasher = StreebogHasher512::new();
+        hasher.update_text(text);
+        hasher.finish();
+        assert_eq!(&streebog512_text(text)[..], &hasher.get_result()[..]);
+        assert_eq!(&streebog512_utf8(data_1).unwrap()[..], &hasher.get_result()[..]);
+        // Valid UTF-8 with multi-byte characters
+        let text = "Стрибог";
+        assert_eq!(&streebog512_utf8(text.as_bytes()).unwrap()[..],
+                   &streebog512_text(text)[..]);
+        // data_2 is cp1251-encoded text, which is not valid UTF-8
+        assert!(streebog512_utf8(data_2).is_err());
+    }
+
+    #[test]
+    fn test_hash_fixed_records512() {
+        let mut records = [[0u8; 64]; 3];
+        records[0].copy_from_slice(&data_2[..64]);
+        records[1] = [0xfcu8; 64];
+        let result = hash_fixed_records512(&records);
+        assert_eq!(result.len(), 3);
+        for (record, digest) in records.iter().zip(result.iter()) {
+            assert_eq!(&digest[..], &hash512(record)[..]);
+        }
+        assert!(hash_fixed_records512(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        fn assert_send<T: Send + 'static>() {}
+        assert_send::<StreebogHasher256>();
+        assert_send::<StreebogHasher512>();
+        assert_send::<SendableState>();
+
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_2_part_1);
+        let state = hasher.snapshot();
+        let result = std::thread::spawn(move || state.finish()).join().unwrap();
+        hasher.finish();
+        assert_eq!(result, hasher.get_result());
+        assert_eq!(hasher.snapshot().finish(), hasher.get_result());
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2);
+        let state = hasher.snapshot();
+        let result = std::thread::spawn(move || state.finish()).join().unwrap();
+        hasher.finish();
+        assert_eq!(result, hasher.get_result());
+    }
+
+    #[test]
+    fn test_streebog_fc_block() {
+        // 0xfc is the value of S(h ^ N) for zero inputs; check 64-byte message (one full
+        // block) and 128-byte message from docs example
+        let should_be = [0x88, 0x7d, 0xd3, 0xae, 0x6f, 0x49, 0x42, 0x47, 0xe5, 0xdf, 0x9a, 0x27,
+                         0xa7, 0xfb, 0xb2, 0x33, 0x24, 0x5e, 0x2f, 0xeb, 0x0d, 0xa2, 0x3d, 0xf4,
+                         0x1b, 0x11, 0xcb, 0xd6, 0x85, 0xf9, 0xeb, 0x37, 0xf2, 0x62, 0x09, 0x02,
+                         0x66, 0x60, 0xb5, 0x10, 0x6f, 0x2e, 0x22, 0xf9, 0x72, 0x91, 0x64, 0x09,
+                         0x0a, 0xd5, 0xae, 0x06, 0x2a, 0x80, 0xe2, 0xcf, 0x22, 0x29, 0x44, 0xf2,
+                         0x0d, 0xab, 0xa4, 0x8b];
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(&[0xfcu8; 64]);
+        hasher.finish();
+        assert_eq!(&hasher.get_result()[..], &should_be[..]);
+
+        let should_be = [0x92, 0x32, 0x59, 0xcf, 0x62, 0xb6, 0xb6, 0x13, 0x6d, 0x0e, 0x09, 0x8d,
+                         0xe7, 0x1d, 0x4f, 0xc5, 0x5d, 0xfb, 0x0a, 0xb3, 0xc7, 0x23, 0x57, 0x0e,
+                         0xdf, 0x10, 0x0c, 0xfd, 0x0e, 0x0f, 0x3d, 0xe9];
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(&[0xfcu8; 64]);
+        hasher.finish();
+        assert_eq!(&hasher.get_result()[..], &should_be[..]);
+
+        let should_be = [0xca, 0x31, 0x28, 0xee, 0x0b, 0x61, 0xd4, 0xed, 0x15, 0xea, 0x63, 0xe4,
+                         0x95, 0x7a, 0xfd, 0x8a, 0x1a, 0x63, 0x4d, 0x69, 0x2e, 0x97, 0x5f, 0xe2,
+                         0x98, 0x08, 0xac, 0x16, 0xb2, 0x49, 0x1a, 0x70, 0x1a, 0x9f, 0x5f, 0x2b,
+                         0xb8, 0x39, 0x8f, 0x52, 0x6b, 0x66, 0x3c, 0xeb, 0x90, 0x91, 0xc3, 0x7e,
+                         0xbd, 0xd0, 0xa2, 0x50, 0x19, 0x0b, 0x5e, 0x2b, 0x29, 0x8d, 0xf5, 0x3f,
+                         0x22, 0xd7, 0x13, 0xa4];
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(&[0xfcu8; 128]);
+        hasher.finish();
+        assert_eq!(&hasher.get_result()[..], &should_be[..]);
+
+        let should_be = [0xcf, 0xd5, 0x2f, 0x43, 0x12, 0x8b, 0xc8, 0xb1, 0xc0, 0x6e, 0x9a, 0x30,
+                         0x17, 0x25, 0x54, 0x68, 0xaf, 0xd3, 0xbf, 0x77, 0x46, 0xfe, 0x86, 0x2d,
+                         0xd7, 0xfa, 0xa3, 0x4c, 0x89, 0x7c, 0x08, 0x84];
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(&[0xfcu8; 128]);
+        hasher.finish();
+        assert_eq!(&hasher.get_result()[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_reset_to_prefix() {
+        let key = [0x5au8; 100];
+        let messages: [&[u8]; 4] = [&[], data_1, data_2, &[0xfcu8; 128]];
+
+        let mut hasher = StreebogHasher512::with_prefix(&key);
+        for message in messages.iter() {
+            let mut expected = StreebogHasher512::new();
+            expected.update(&key);
+            expected.update(message);
+            expected.finish();
+
+            hasher.update(message);
+            hasher.finish();
+            assert_eq!(hasher.get_result(), expected.get_result());
+            hasher.reset_to_prefix();
+        }
+
+        let mut hasher = StreebogHasher256::with_prefix(&key);
+        for message in messages.iter() {
+            let mut expected = StreebogHasher256::new();
+            expected.update(&key);
+            expected.update(message);
+            expected.finish();
+
+            hasher.update(message);
+            hasher.finish();
+            assert_eq!(hasher.get_result(), expected.get_result());
+            hasher.reset_to_prefix();
+        }
+
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_1);
+        hasher.reset_to_prefix();
+        assert!(hasher.state_eq(&StreebogHasher512::new()));
+    }
+
+    #[test]
+    fn test_streebog512_oneshot_nostd() {
+        let mut scratch = [0u8; 64];
+        let messages: [&[u8]; 5] = [&[], data_1, data_2, &[0xfcu8; 64], &[0xfcu8; 200]];
+        for message in messages.iter() {
+            assert_eq!(streebog512_oneshot_nostd(message, &mut scratch)[..],
+                       hash512(message)[..]);
+            let tail = &message[message.len() - message.len() % 64..];
+            assert_eq!(scratch[..], pad_data(tail)[..]);
+        }
+    }
+
+    // Checked at compile time, so generic code can rely on them in array lengths
+    const _: () = assert!(StreebogHasher512::OUTPUT_SIZE == 64);
+    const _: () = assert!(StreebogHasher256::OUTPUT_SIZE == 32);
+    const _: () = assert!(StreebogHasher512::BLOCK_SIZE == StreebogHasher256::BLOCK_SIZE);
+
+    #[test]
+    fn test_streebog_params() {
+        fn params<T: StreebogParams>() -> (usize, usize, usize) {
+            (T::ROUNDS, T::BLOCK_SIZE, T::OUTPUT_SIZE)
+        }
+        assert_eq!(params::<StreebogHasher512>(), (12, 64, 64));
+        assert_eq!(params::<StreebogHasher256>(), (12, 64, 32));
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(hasher.get_result().len(), StreebogHasher256::OUTPUT_SIZE);
+    }
+
+    #[test]
+    fn test_hash_and_verify() {
+        let digest = hash512(data_2);
+        assert_eq!(hash_and_verify512(data_2, &digest).map(|x| x.to_vec()), Some(digest.to_vec()));
+        let mut wrong_digest = digest;
+        wrong_digest[63] ^= 0x1;
+        assert!(hash_and_verify512(data_2, &wrong_digest).is_none());
+        assert!(hash_and_verify512(data_1, &digest).is_none());
+
+        let digest = hash256(data_2);
+        assert_eq!(hash_and_verify256(data_2, &digest), Some(digest));
+        let mut wrong_digest = digest;
+        wrong_digest[0] ^= 0x80;
+        assert!(hash_and_verify256(data_2, &wrong_digest).is_none());
+        assert!(hash_and_verify256(data_1, &digest).is_none());
+    }
+
+    #[test]
+    fn test_update_from_reader() {
+        let mut data = data_2.to_vec();
+        data.extend_from_slice(&[0xfcu8; 200000]);
+        data.extend_from_slice(data_1);
+
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_2);
+        let mut reader = io::Cursor::new(vec![0xfcu8; 200000]);
+        assert_eq!(hasher.update_from_reader(&mut reader).unwrap(), 200000);
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(hasher.result[..], hash512(&data)[..]);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2);
+        assert_eq!(hasher.update_from_reader(&mut io::Cursor::new(&data[72..200072])).unwrap(),
+                   200000);
+        assert_eq!(hasher.update_from_reader(&mut io::empty()).unwrap(), 0);
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(hasher.result, hash256(&data));
+    }
+
+    #[test]
+    fn test_update_from_reader_2() {
+        let mut hasher = StreebogHasher512::new();
+        assert_eq!(hasher.update_from_reader(&mut io::Cursor::new(data_2)).unwrap(), 72);
+        hasher.finish();
+        assert_eq!(hasher.result[..4], [0x28, 0xfb, 0xc9, 0xba]);
+        assert_eq!(hasher.result[60..], [0x22, 0xe6, 0x88, 0x1e]);
+
+        // Interrupted reads are retried, other errors are returned after data read before them
+        struct FlakyReader(usize);
+        impl Read for FlakyReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.0 += 1;
+                match self.0 {
+                    1 => Err(io::Error::from(io::ErrorKind::Interrupted)),
+                    2 => {
+                        buf[..data_1.len()].copy_from_slice(data_1);
+                        Ok(data_1.len())
+                    }
+                    _ => Err(io::Error::other("broken")),
+                }
+            }
+        }
+        let mut hasher = StreebogHasher512::new();
+        let err = hasher.update_from_reader(&mut FlakyReader(0)).unwrap_err();
+        assert_eq!(format!("{}", err), "broken");
+        hasher.finish();
+        assert_eq!(hasher.result, hash512(data_1));
+    }
+
+    #[test]
+    fn test_hash_reader() {
+        assert_eq!(hash512_reader(&mut io::Cursor::new(data_1)).unwrap()[..],
+                   hash512(data_1)[..]);
+        assert_eq!(hash512_reader(&mut io::Cursor::new(data_1)).unwrap()[..4],
+                   [0x48, 0x6f, 0x64, 0xc1]);
+        assert_eq!(hash256_reader(&mut io::Cursor::new(data_1)).unwrap(), hash256(data_1));
+        assert_eq!(hash256_reader(&mut io::empty()).unwrap(), hash256(b""));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_1);
+        assert_eq!(hasher.fingerprint(4), "");
+        hasher.finish();
+        assert_eq!(hasher.fingerprint(4), "486f64c1");
+        assert_eq!(hasher.fingerprint(0), "");
+        assert_eq!(hasher.fingerprint(100), &hasher.get_result_str()[2..]);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(hasher.fingerprint(4), "00557be5");
+        assert_eq!(hasher.fingerprint(32), &hasher.get_result_str()[2..]);
+    }
+
+    #[test]
+    fn test_streebog512_files() {
+        let dir = std::env::temp_dir();
+        let path_1 = dir.join(format!("streebog_hash_test_files_{}_1", std::process::id()));
+        let path_2 = dir.join(format!("streebog_hash_test_files_{}_2", std::process::id()));
+        std::fs::write(&path_1, data_2_part_1).unwrap();
+        std::fs::write(&path_2, data_2_part_2).unwrap();
+
+        let result = streebog512_files(&[&path_1, &path_2]);
+        let reversed_result = streebog512_files(&[&path_2, &path_1]);
+        let missing_result = streebog512_files(&[&path_1, &dir.join("streebog_hash_missing")]);
+        std::fs::remove_file(&path_1).unwrap();
+        std::fs::remove_file(&path_2).unwrap();
+
+        assert_eq!(result.unwrap()[..], hash512(data_2)[..]);
+        assert!(reversed_result.unwrap()[..] != hash512(data_2)[..]);
+        assert!(missing_result.is_err());
+        let empty: [&str; 0] = [];
+        assert_eq!(streebog512_files(&empty).unwrap()[..], hash512(&[])[..]);
+    }
+
+    #[cfg(feature = "legacy-insecure-mac")]
+    #[test]
+    fn test_prefix_mac() {
+        assert_eq!(prefix_mac512(data_2_part_1, data_2_part_2)[..], hash512(data_2)[..]);
+        assert_eq!(prefix_mac256(data_2_part_1, data_2_part_2), hash256(data_2));
+        assert_eq!(prefix_mac256(&data_2[..36], &data_2[36..]),
+                   prefix_mac256(data_2_part_1, data_2_part_2));
+    }
+
+    #[test]
+    fn test_streebog512_vectored() {
+        let slices = [IoSlice::new(&data_2[..10]), IoSlice::new(&[]), IoSlice::new(&data_2[10..])];
+        assert_eq!(streebog512_vectored(&slices)[..], hash512(data_2)[..]);
+        let slices = [IoSlice::new(data_1), IoSlice::new(data_2_part_1),
+                      IoSlice::new(data_2_part_2)];
+        let mut data = data_1.to_vec();
+        data.extend_from_slice(data_2);
+        assert_eq!(streebog512_vectored(&slices)[..], hash512(&data)[..]);
+        assert_eq!(streebog512_vectored(&[])[..], hash512(&[])[..]);
+    }
+
+    #[test]
+    fn test_raw_state() {
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2);
+        let raw_state = hasher.raw_state();
+        assert_eq!(raw_state[..32], hasher.get_result()[..]);
+        assert_eq!(raw_state[..32], hash256(data_2)[..]);
+        assert!(raw_state[32..] != [0u8; 32][..]);
+        assert!(raw_state[..] != hash512(data_2)[..]);
+        // Repeated call returns the same state
+        assert_eq!(hasher.raw_state()[..], raw_state[..]);
+    }
+
+    #[test]
+    fn test_streebog_byte_by_byte() {
+        let mut data = data_2.to_vec();
+        data.extend_from_slice(data_1);
+        data.extend_from_slice(&[0xfcu8; 128]);
+
+        let mut hasher = StreebogHasher512::new();
+        for (i, byte) in data.iter().enumerate() {
+            assert_eq!(hasher.update_reporting(&[*byte]), (i + 1) % 64);
+        }
+        hasher.finish();
+        assert_eq!(hasher.result[..], hash512(&data)[..]);
+
+        let mut hasher = StreebogHasher256::new();
+        for byte in data_1.iter() {
+            hasher.update(&[*byte]);
+        }
+        hasher.finish();
+        assert_eq!(hasher.get_result_str(),
+                   "0x00557be5e584fd52a449b16b0251d05d27f94ab76cbaa6da890b59d8ef1e159d");
+    }
+
+    #[test]
+    fn test_update_until() {
+        let mut stream = data_2.to_vec();
+        stream.extend_from_slice(data_1);
+
+        let mut hasher = StreebogHasher512::new();
+        assert_eq!(hasher.update_until(&stream[..30], 72), (30, false));
+        assert_eq!(hasher.update_until(&stream[30..40], 72), (10, false));
+        assert_eq!(hasher.update_until(&stream[40..], 72), (32, true));
+        assert_eq!(hasher.update_until(&stream[72..], 72), (0, true));
+        hasher.finish();
+        assert_eq!(hasher.result[..], hash512(data_2)[..]);
+
+        let mut hasher = StreebogHasher256::with_prefix(data_2_part_1);
+        assert_eq!(hasher.update_until(&data_2_part_2[..5], 72), (5, false));
+        assert_eq!(hasher.update_until(&data_2_part_2[5..], 72), (30, true));
+        hasher.finish();
+        assert_eq!(hasher.result, hash256(data_2));
+        assert_eq!(hasher.update_until(data_1, 1000), (0, false));
+    }
+
+    #[test]
+    fn test_xor_into() {
+        fn acc256(elements: &[&[u8]]) -> [u8; 32] {
+            let mut acc = [0u8; 32];
+            for element in elements.iter() {
+                let mut hasher = StreebogHasher256::new();
+                hasher.update(element);
+                hasher.xor_into(&mut acc);
+            }
+            acc
+        }
+        let acc = acc256(&[data_1, data_2, b"abc"]);
+        assert_eq!(acc256(&[b"abc", data_1, data_2]), acc);
+        assert_eq!(acc256(&[data_2, b"abc", data_1]), acc);
+        assert_eq!(acc256(&[data_2, b"abc", data_1, b"x", b"x"]), acc);
+        assert_eq!(acc256(&[data_1, data_1]), [0u8; 32]);
+
+        let mut acc = [0u8; 64];
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_1);
+        hasher.xor_into(&mut acc);
+        assert_eq!(acc[..], hash512(data_1)[..]);
+        hasher.xor_into(&mut acc);
+        assert_eq!(acc[..], [0u8; 64][..]);
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_compress_only() {
+        let mut block = [0u8; 64];
+        block[..63].copy_from_slice(data_1);
+        block[63] = 0x1;
+        let mut should_be = g_N([0u8; 64], [0u8; 64], block);
+        should_be.reverse();
+        assert_eq!(&compress_only(&[block])[..], &should_be[..]);
+        assert_eq!(&compress_only(&[])[..], &[0u8; 64][..]);
+
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(&[0xfcu8; 128]);
+        let mut chaining_value = hasher.ctx.hash;
+        chaining_value.reverse();
+        assert_eq!(&compress_only(&[[0xfcu8; 64]; 2])[..], &chaining_value[..]);
+    }
+
+    #[test]
+    fn test_streebog512_lines() {
+        let texts: [&[u8]; 4] = [b"first\nsecond\r\n\nlast without newline", b"\n\n", b"",
+                                 b"\xff\xfe binary\r\n"];
+        for text in texts.iter() {
+            let mut hasher = StreebogHasher512::new();
+            hasher.update_from_reader(&mut io::Cursor::new(text)).unwrap();
+            hasher.finish();
+            let lines_result = streebog512_lines(io::BufReader::with_capacity(3, *text)).unwrap();
+            assert_eq!(lines_result[..], hasher.result[..]);
+            assert_eq!(lines_result[..], hash512(text)[..]);
+        }
+    }
+
+    #[test]
+    fn test_update_integers_be() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.update_u64_be(0x0102030405060708);
+        hasher.update_u32_be(0x090a0b0c);
+        hasher.finish();
+        assert_eq!(hasher.result[..], hash512(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])[..]);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update_u32_be(1);
+        hasher.finish();
+        assert_eq!(hasher.result, hash256(&[0, 0, 0, 1]));
+    }
+
+    #[test]
+    fn test_streebog512_range() {
+        assert_eq!(streebog512_range(data_2, 37..72)[..], hash512(data_2_part_2)[..]);
+        assert_eq!(streebog512_range(data_2, 0..37)[..], hash512(data_2_part_1)[..]);
+        assert_eq!(streebog512_range(data_2, 10..10)[..], hash512(&[])[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "range 40..73 is out of bounds of data of length 72")]
+    fn test_streebog512_range_out_of_bounds() {
+        streebog512_range(data_2, 40..73);
+    }
+
+    #[test]
+    fn test_commitment() {
+        let nonce = [0x42u8; 32];
+        let commitment = commit512(&nonce, data_2);
+        let mut message = nonce.to_vec();
+        message.extend_from_slice(data_2);
+        assert_eq!(commitment[..], hash512(&message)[..]);
+
+        assert!(verify_commitment512(&commitment, &nonce, data_2));
+        assert!(!verify_commitment512(&commitment, &nonce, data_1));
+        assert!(!verify_commitment512(&commitment, &[0x43u8; 32], data_2));
+        let mut wrong_commitment = commitment;
+        wrong_commitment[10] ^= 0x4;
+        assert!(!verify_commitment512(&wrong_commitment, &nonce, data_2));
+    }
+
+    #[test]
+    fn test_finalize_send() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_2);
+        hasher.finalize_send(tx).unwrap();
+        assert_eq!(rx.recv().unwrap()[..], hash512(data_2)[..]);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2);
+        std::thread::spawn(move || hasher.finalize_send(tx).unwrap());
+        assert_eq!(rx.recv().unwrap(), hash256(data_2));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        drop(rx);
+        let error = StreebogHasher256::new().finalize_send(tx).unwrap_err();
+        assert_eq!(error.0, hash256(&[]));
+    }
+
+    #[test]
+    fn test_finalize_with_len() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_2_part_1);
+        hasher.update(data_2_part_2);
+        let (digest, len) = hasher.finalize_with_len();
+        assert_eq!(digest[..], hash512(data_2)[..]);
+        assert_eq!(len, 72);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(&[0xfcu8; 1000]);
+        hasher.update(data_1);
+        assert_eq!(hasher.finalize_with_len().1, 1063);
+        assert_eq!(StreebogHasher256::new().finalize_with_len(), (hash256(&[]), 0));
+    }
+
+    #[test]
+    fn test_streebog512_try_chunks() {
+        let chunks: Vec<Result<Vec<u8>, &str>> = vec![Ok(data_2_part_1.to_vec()),
+                                                      Ok(data_2_part_2.to_vec())];
+        assert_eq!(streebog512_try_chunks(chunks).unwrap()[..], hash512(data_2)[..]);
+
+        let mut consumed = 0;
+        let chunks = vec![Ok(data_2_part_1.to_vec()), Ok(data_2_part_2.to_vec()), Err("broken"),
+                          Ok(data_1.to_vec())]
+            .into_iter()
+            .inspect(|_| consumed += 1);
+        assert_eq!(streebog512_try_chunks(chunks), Err("broken"));
+        assert_eq!(consumed, 3);
+    }
+
+    #[test]
+    fn test_update_integer_slices() {
+        fn hash_with<F: Fn(&mut StreebogHasher256)>(f: F) -> [u8; 32] {
+            let mut hasher = StreebogHasher256::new();
+            f(&mut hasher);
+            hasher.finish();
+            hasher.result
+        }
+        assert_eq!(hash_with(|h| h.update_u32_slice_le(&[0x01020304])),
+                   hash256(&[0x04, 0x03, 0x02, 0x01]));
+        assert_eq!(hash_with(|h| h.update_u32_slice_be(&[0x01020304, 0x05])),
+                   hash256(&[0x01, 0x02, 0x03, 0x04, 0, 0, 0, 0x05]));
+        assert_eq!(hash_with(|h| h.update_u64_slice_le(&[0x0102030405060708])),
+                   hash256(&[8, 7, 6, 5, 4, 3, 2, 1]));
+        assert_eq!(hash_with(|h| h.update_u64_slice_be(&[0x0102030405060708])),
+                   hash256(&[1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(hash_with(|h| h.update_u64_slice_be(&[])), hash256(&[]));
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_heapless() {
+        let mut hasher = StreebogHasher512::new();
+        assert_eq!(hasher.get_result_str_heapless(), "");
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(hasher.get_result_str_heapless().as_str(), hasher.get_result_str());
 
-    static data_2_part_2: &'static [u8] = &[0xff, 0x20, 0xf1, 0xf2, 0xf0, 0xe5, 0xeb, 0xe0, 0xec,
-                                            0xe8, 0x20, 0xed, 0xe0, 0x20, 0xf5, 0xf0, 0xe0, 0xe1,
-                                            0xf0, 0xfb, 0xff, 0x20, 0xef, 0xeb, 0xfa, 0xea, 0xfb,
-                                            0x20, 0xc8, 0xe3, 0xee, 0xf0, 0xe5, 0xe2, 0xfb];
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(hasher.get_result_str_heapless().as_str(), hasher.get_result_str());
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2);
+        assert_eq!(hasher.into_pending_heapless()[..], data_2[64..]);
+    }
 
     #[test]
-    #[should_panic]
-    fn test_streebog_update_1() {
-        let mut data = Vec::new();
-        data.extend_from_slice(data_1);
-        let should_be = [0xfd, 0x10, 0x2c, 0xf8, 0x81, 0x2c, 0xcb, 0x11, 0x91, 0xea, 0x34, 0xaf,
-                         0x21, 0x39, 0x4f, 0x38, 0x17, 0xa8, 0x66, 0x41, 0x44, 0x5a, 0xa9, 0xa6,
-                         0x26, 0x48, 0x8a, 0xdb, 0x33, 0x73, 0x8e, 0xbd, 0x27, 0x54, 0xf6, 0x90,
-                         0x8c, 0xbb, 0xba, 0xc5, 0xd3, 0xed, 0x0f, 0x52, 0x2c, 0x50, 0x81, 0x5c,
-                         0x95, 0x41, 0x35, 0x79, 0x3f, 0xb1, 0xf5, 0xd9, 0x05, 0xfe, 0xe4, 0x73,
-                         0x6b, 0x3b, 0xda, 0xe2];
+    fn test_branch() {
         let mut hasher = StreebogHasher512::new();
-        hasher.update(&super::pad_data(data)[..]);
-        assert_eq!(hasher.ctx.data, Vec::new());
-        assert_eq!(&hasher.ctx.hash[..], &should_be[..]);
+        hasher.update(data_2_part_1);
+        let mut branch = hasher.branch();
+        hasher.update(data_2_part_2);
+        branch.update(data_1);
+        hasher.finish();
+        branch.finish();
+        assert_eq!(hasher.result[..], hash512(data_2)[..]);
+        assert_eq!(branch.result[..], hash512(&[data_2_part_1, data_1].concat())[..]);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2_part_1);
+        let mut branch = hasher.branch();
+        branch.update(data_2_part_2);
+        branch.finish();
+        hasher.finish();
+        assert_eq!(branch.result, hash256(data_2));
+        assert_eq!(hasher.result, hash256(data_2_part_1));
     }
 
     #[test]
-    fn test_streebog512_final_1() {
+    fn test_with_max_len() {
+        let mut hasher = StreebogHasher512::with_max_len(72);
+        assert!(hasher.try_update(data_2_part_1).is_ok());
+        assert!(hasher.try_update(data_2_part_2).is_ok());
+        assert!(hasher.try_finish().is_ok());
+        assert_eq!(hasher.result[..], hash512(data_2)[..]);
+
+        let mut hasher = StreebogHasher256::with_max_len(71);
+        assert!(hasher.try_update(data_2_part_1).is_ok());
+        match hasher.try_update(data_2_part_2) {
+            Err(StreebogError::LengthExceeded { max: 71 }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        hasher.update(&[]);
+        hasher.finish();
+        assert!(hasher.get_result().is_empty());
+        assert!(hasher.try_finish().is_err());
+        assert_eq!(hasher.update_until(data_1, 100), (0, false));
+
+        hasher.reset();
+        hasher.update(data_2_part_1);
+        assert_eq!(hasher.update_until(data_2_part_2, 100), (0, false));
+        assert!(hasher.try_finish().is_err());
+    }
+
+    #[test]
+    fn test_already_finished() {
+        let mut hasher = StreebogHasher512::new();
+        assert!(hasher.try_update(data_2_part_1).is_ok());
+        assert!(hasher.try_update(data_2_part_2).is_ok());
+        assert!(hasher.try_finish().is_ok());
+        assert_eq!(hasher.result[..], hash512(data_2)[..]);
+        match hasher.try_update(data_1) {
+            Err(HasherError::AlreadyFinished) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        match hasher.try_finish() {
+            Err(HasherError::AlreadyFinished) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert_eq!(hasher.result[..], hash512(data_2)[..]);
+
+        hasher.reset();
+        assert!(hasher.try_update(data_1).is_ok());
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.finish();
+        assert!(hasher.try_update(data_1).is_err());
+        assert!(hasher.try_finish().is_err());
+        assert_eq!(hasher.result, hash256(b""));
+    }
+
+    #[test]
+    fn test_finalize_reset() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_1);
+        assert_eq!(hasher.finalize_reset()[..], hash512(data_1)[..]);
+        hasher.update(data_2);
+        assert_eq!(hasher.finalize_reset()[..], hash512(data_2)[..]);
+        assert_eq!(hasher.finalize_reset()[..], hash512(b"")[..]);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2_part_1);
+        hasher.update(data_2_part_2);
+        assert_eq!(hasher.finalize_reset(), hash256(data_2));
+        hasher.update(data_1);
+        assert_eq!(hasher.finalize_reset(), hash256(data_1));
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_finalize_tagged() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_2);
+        assert_eq!(hasher.finalize_tagged(0)[..], hash512(data_2)[..]);
+        assert_eq!(hasher.finalize_tagged(0x5a)[..], hash512(data_2)[..]);
+
+        // Tag is XORed into the last byte of padded block (padding marker for 63-byte data)
+        let mut block = [0u8; 64];
+        block[..63].copy_from_slice(data_1);
+        block[63] = 0x1 ^ 0x5a;
+        let mut len = [0u8; 64];
+        len[0] = 0xf8;
+        len[1] = 0x01;
+        let hash = g_N([0u8; 64], [0u8; 64], block);
+        let hash = g_N([0u8; 64], hash, len);
+        let mut should_be = g_N([0u8; 64], hash, block);
+        should_be.reverse();
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_1);
+        assert_eq!(hasher.finalize_tagged(0x5a)[..], should_be[..]);
+        assert_eq!(hasher.get_result()[..], should_be[..]);
+        assert_ne!(should_be[..], hash512(data_1)[..]);
+    }
+
+    #[test]
+    fn test_iv() {
+        fn check<H: StreebogHasher>(iv: [u8; 64], hash: fn(&H) -> [u8; 64], should_be: &[u8]) {
+            let mut hasher = H::new();
+            assert_eq!(hash(&hasher)[..], iv[..]);
+            hasher.update(data_2);
+            hasher.finish();
+            hasher.reset();
+            assert_eq!(hash(&hasher)[..], iv[..]);
+            hasher.update(data_1);
+            hasher.finish();
+            assert_eq!(&hasher.get_result()[..], should_be);
+        }
+        check::<StreebogHasher512>([0u8; 64], |h| h.ctx.hash, &hash512(data_1));
+        check::<StreebogHasher256>([1u8; 64], |h| h.ctx.hash, &hash256(data_1));
+    }
+
+    #[test]
+    fn test_update_counting() {
+        let mut hasher = StreebogHasher512::new();
+        assert_eq!(hasher.update_counting(&[0xfcu8; 100]), 1);
+        assert_eq!(hasher.update_counting(&[0xfcu8; 30]), 1);
+        assert_eq!(hasher.update_counting(&[0xfcu8; 10]), 0);
+        assert_eq!(hasher.blocks_compressed(), 2);
+
+        let mut hasher = StreebogHasher256::new();
+        assert_eq!(hasher.update_counting(&[0xfcu8; 64 * 3]), 3);
+        hasher.finish();
+        assert_eq!(hasher.update_counting(&[0xfcu8; 64]), 0);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut hasher = StreebogHasher512::new();
+        let copied = io::copy(&mut io::Cursor::new(data_2), &mut hasher).unwrap();
+        assert_eq!(copied, data_2.len() as u64);
+        hasher.flush().unwrap();
+        hasher.finish();
+        assert_eq!(hasher.result[..], hash512(data_2)[..]);
+        let err = hasher.write(data_1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+
+        let mut hasher = StreebogHasher256::with_max_len(64);
+        assert_eq!(hasher.write(data_1).unwrap(), data_1.len());
+        assert_eq!(hasher.write(data_1).unwrap_err().kind(), io::ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_streebog256_reset() {
+        let mut should_be = [0x9d, 0x15, 0x1e, 0xef, 0xd8, 0x59, 0x0b, 0x89, 0xda, 0xa6, 0xba, 0x6c,
+                             0xb7, 0x4a, 0xf9, 0x27, 0x5d, 0xd0, 0x51, 0x02, 0x6b, 0xb1, 0x49, 0xa4,
+                             0x52, 0xfd, 0x84, 0xe5, 0xe5, 0x7b, 0x55, 0x00];
+        // reverse order cuz output should be in big-endian
+        should_be.reverse();
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
+        hasher.finish();
+        hasher.reset();
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(&hasher.get_result()[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_hash() {
         let mut should_be = [0x1b, 0x54, 0xd0, 0x1a, 0x4a, 0xf5, 0xb9, 0xd5, 0xcc, 0x3d, 0x86, 0xd6,
                              0x8d, 0x28, 0x54, 0x62, 0xb1, 0x9a, 0xbc, 0x24, 0x75, 0x22, 0x2f, 0x35,
                              0xc0, 0x85, 0x12, 0x2b, 0xe4, 0xba, 0x1f, 0xfa, 0x00, 0xad, 0x30, 0xf8,
                              0x76, 0x7b, 0x3a, 0x82, 0x38, 0x4c, 0x65, 0x74, 0xf0, 0x24, 0xc3, 0x11,
                              0xe2, 0xa4, 0x81, 0x33, 0x2b, 0x08, 0xef, 0x7f, 0x41, 0x79, 0x78, 0x91,
                              0xc1, 0x64, 0x6f, 0x48];
-        // reverse order cuz output should be in big-endian
         should_be.reverse();
-        let mut hasher = StreebogHasher512::new();
-        hasher.update(data_1);
-        hasher.finish();
-        let result = hasher.get_result();
-        assert_eq!(&result[..], &should_be[..]);
+        assert_eq!(hash512(data_1)[..], should_be[..]);
+
+        let mut should_be = [0x9d, 0xd2, 0xfe, 0x4e, 0x90, 0x40, 0x9e, 0x5d, 0xa8, 0x7f, 0x53, 0x97,
+                             0x6d, 0x74, 0x05, 0xb0, 0xc0, 0xca, 0xc6, 0x28, 0xfc, 0x66, 0x9a, 0x74,
+                             0x1d, 0x50, 0x06, 0x3c, 0x55, 0x7e, 0x8f, 0x50];
+        should_be.reverse();
+        assert_eq!(hash256(data_2), should_be);
     }
 
     #[test]
-    fn test_streebog256_final_1() {
+    fn test_finalize() {
+        let mut should_be = [0x1b, 0x54, 0xd0, 0x1a, 0x4a, 0xf5, 0xb9, 0xd5, 0xcc, 0x3d, 0x86, 0xd6,
+                             0x8d, 0x28, 0x54, 0x62, 0xb1, 0x9a, 0xbc, 0x24, 0x75, 0x22, 0x2f, 0x35,
+                             0xc0, 0x85, 0x12, 0x2b, 0xe4, 0xba, 0x1f, 0xfa, 0x00, 0xad, 0x30, 0xf8,
+                             0x76, 0x7b, 0x3a, 0x82, 0x38, 0x4c, 0x65, 0x74, 0xf0, 0x24, 0xc3, 0x11,
+                             0xe2, 0xa4, 0x81, 0x33, 0x2b, 0x08, 0xef, 0x7f, 0x41, 0x79, 0x78, 0x91,
+                             0xc1, 0x64, 0x6f, 0x48];
+        should_be.reverse();
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_1);
+        let result: [u8; 64] = hasher.finalize().0;
+        assert_eq!(result[..], should_be[..]);
+
         let mut should_be = [0x9d, 0x15, 0x1e, 0xef, 0xd8, 0x59, 0x0b, 0x89, 0xda, 0xa6, 0xba, 0x6c,
                              0xb7, 0x4a, 0xf9, 0x27, 0x5d, 0xd0, 0x51, 0x02, 0x6b, 0xb1, 0x49, 0xa4,
                              0x52, 0xfd, 0x84, 0xe5, 0xe5, 0x7b, 0x55, 0x00];
-        // reverse order cuz output should be in big-endian
         should_be.reverse();
         let mut hasher = StreebogHasher256::new();
         hasher.update(data_1);
+        let result: [u8; 32] = hasher.finalize().0;
+        assert_eq!(result, should_be);
+
+        // Already finished hasher is not finished again
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
         hasher.finish();
-        let result = hasher.get_result();
-        assert_eq!(&result[..], &should_be[..]);
+        assert_eq!(hasher.finalize().0, should_be);
     }
 
     #[test]
-    fn test_streebog512_final_2() {
-        let mut should_be = [0x1e, 0x88, 0xe6, 0x22, 0x26, 0xbf, 0xca, 0x6f, 0x99, 0x94, 0xf1, 0xf2,
-                             0xd5, 0x15, 0x69, 0xe0, 0xda, 0xf8, 0x47, 0x5a, 0x3b, 0x0f, 0xe6, 0x1a,
-                             0x53, 0x00, 0xee, 0xe4, 0x6d, 0x96, 0x13, 0x76, 0x03, 0x5f, 0xe8, 0x35,
-                             0x49, 0xad, 0xa2, 0xb8, 0x62, 0x0f, 0xcd, 0x7c, 0x49, 0x6c, 0xe5, 0xb3,
-                             0x3f, 0x0c, 0xb9, 0xdd, 0xdc, 0x2b, 0x64, 0x60, 0x14, 0x3b, 0x03, 0xda,
-                             0xba, 0xc9, 0xfb, 0x28];
-        should_be.reverse();
+    fn test_clone() {
         let mut hasher = StreebogHasher512::new();
-        hasher.update(data_2);
+        hasher.update(data_2_part_1);
+        let mut cloned = hasher.clone();
+        hasher.update(data_2_part_2);
+        cloned.update(data_2_part_2);
         hasher.finish();
-        let result = hasher.get_result();
-        assert_eq!(&result[..], &should_be[..]);
+        cloned.finish();
+        assert_eq!(hasher.result[..], hash512(data_2)[..]);
+        assert_eq!(cloned.result[..], hash512(data_2)[..]);
     }
 
+    #[cfg(feature = "zeroize")]
     #[test]
-    fn test_streebog256_final_2() {
-        let mut should_be = [0x9d, 0xd2, 0xfe, 0x4e, 0x90, 0x40, 0x9e, 0x5d, 0xa8, 0x7f, 0x53, 0x97,
-                             0x6d, 0x74, 0x05, 0xb0, 0xc0, 0xca, 0xc6, 0x28, 0xfc, 0x66, 0x9a, 0x74,
-                             0x1d, 0x50, 0x06, 0x3c, 0x55, 0x7e, 0x8f, 0x50];
-        should_be.reverse();
+    fn test_zeroize() {
+        use core::mem::ManuallyDrop;
+
+        let mut hasher = ManuallyDrop::new(StreebogHasher512::with_prefix(data_2));
+        hasher.finish();
+        unsafe { ManuallyDrop::drop(&mut hasher) };
+        // Storage of ManuallyDrop is still valid after drop, so state can be inspected
+        assert_eq!(hasher.result[..], [0u8; 64][..]);
+        assert_eq!(hasher.ctx.hash[..], [0u8; 64][..]);
+        assert_eq!(hasher.ctx.N, [0u64; 8]);
+        assert_eq!(hasher.ctx.sigma, [0u64; 8]);
+        assert_eq!(hasher.ctx.data[..], [0u8; 64][..]);
+        assert_eq!((hasher.ctx.data_len, hasher.ctx.blocks, hasher.ctx.absorbed), (0, 0, 0));
+
         let mut hasher = StreebogHasher256::new();
         hasher.update(data_2);
+        hasher.reset();
+        assert_eq!(hasher.ctx.data[..], [0u8; 64][..]);
+        hasher.update(data_1);
         hasher.finish();
-        let result = hasher.get_result();
-        assert_eq!(&result[..], &should_be[..]);
+        assert_eq!(hasher.result, hash256(data_1));
     }
 
     #[test]
-    fn test_streebog_with_multiple_parts_of_data() {
-        let mut should_be = [0x1e, 0x88, 0xe6, 0x22, 0x26, 0xbf, 0xca, 0x6f, 0x99, 0x94, 0xf1, 0xf2,
-                             0xd5, 0x15, 0x69, 0xe0, 0xda, 0xf8, 0x47, 0x5a, 0x3b, 0x0f, 0xe6, 0x1a,
-                             0x53, 0x00, 0xee, 0xe4, 0x6d, 0x96, 0x13, 0x76, 0x03, 0x5f, 0xe8, 0x35,
-                             0x49, 0xad, 0xa2, 0xb8, 0x62, 0x0f, 0xcd, 0x7c, 0x49, 0x6c, 0xe5, 0xb3,
-                             0x3f, 0x0c, 0xb9, 0xdd, 0xdc, 0x2b, 0x64, 0x60, 0x14, 0x3b, 0x03, 0xda,
-                             0xba, 0xc9, 0xfb, 0x28];
-        should_be.reverse();
+    fn test_ct_eq() {
+        let digest = hash256(data_1);
+        assert!(ct_eq(&digest, &hash256(data_1)));
+        assert!(ct_eq(b"", b""));
+
+        let mut other = digest;
+        other[31] ^= 1;
+        assert!(!ct_eq(&digest, &other));
+
+        assert!(!ct_eq(&digest, &digest[..31]));
+        assert!(!ct_eq(&digest[..0], &digest));
+    }
+
+    #[test]
+    fn test_get_result_into() {
+        let mut out = [0u8; 64];
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_1);
+        assert!(matches!(hasher.get_result_into(&mut out), Err(StreebogError::InvalidState)));
+        hasher.finish();
+        assert!(matches!(hasher.get_result_into(&mut out[..63]),
+                         Err(StreebogError::InvalidLength { expected: 64, actual: 63 })));
+        assert_eq!(hasher.get_result_into(&mut out).unwrap(), 64);
+        assert_eq!(out[..], hasher.get_result()[..]);
+
+        let mut out = [0xffu8; 64];
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(hasher.get_result_into(&mut out).unwrap(), 32);
+        assert_eq!(out[..32], hasher.get_result()[..]);
+        assert_eq!(out[32..], [0xffu8; 32][..]);
+    }
+
+    #[test]
+    fn test_to_hex() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_1);
+        assert_eq!(hasher.to_hex(), "");
+        hasher.finish();
+        let hex = "486f64c1917879417fef082b3381a4e211c324f074654c38823a7b76f830ad00fa1fbae42b1285c\
+                   0352f227524bc9ab16254288dd6863dccd5b9f54a1ad0541b";
+        assert_eq!(hasher.to_hex(), hex);
+        assert_eq!(hasher.to_hex_upper(), hex.to_uppercase());
+        assert_eq!(hasher.get_result_str(), format!("0x{}", hex));
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(hasher.to_hex_upper(),
+                   "00557BE5E584FD52A449B16B0251D05D27F94AB76CBAA6DA890B59D8EF1E159D");
+    }
+
+    #[test]
+    fn test_save_state() {
         let mut hasher = StreebogHasher512::new();
         hasher.update(data_2_part_1);
-        hasher.update(data_2_part_2);
+        let state = hasher.save_state();
+        assert_eq!(state.len(), 212 + data_2_part_1.len());
+        let mut restored = StreebogHasher512::restore_state(&state).unwrap();
+        assert!(restored.state_eq(&hasher));
+        restored.update(data_2_part_2);
+        restored.finish();
+        assert_eq!(restored.result[..], hash512(data_2)[..]);
+        let finished = StreebogHasher512::restore_state(&restored.save_state()).unwrap();
+        assert_eq!(finished.get_result(), restored.get_result());
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(&[0xfcu8; 100]);
+        hasher.update(data_2_part_1);
+        let mut restored = StreebogHasher256::restore_state(&hasher.save_state()).unwrap();
+        restored.update(data_2_part_2);
+        let mut data = vec![0xfcu8; 100];
+        data.extend_from_slice(data_2);
+        assert_eq!(restored.finalize_with_len(), (hash256(&data), 172));
+
+        // Mismatched or malformed state
+        let state = hasher.save_state();
+        match StreebogHasher512::restore_state(&state) {
+            Err(StreebogError::InvalidState) => {}
+            _ => panic!("state of 256-bit hasher restored as 512-bit"),
+        }
+        let mut wrong_version = state.clone();
+        wrong_version[0] = 2;
+        match StreebogHasher256::restore_state(&wrong_version) {
+            Err(StreebogError::UnsupportedVersion) => {}
+            _ => panic!("unsupported version accepted"),
+        }
+        match StreebogHasher256::restore_state(&state[..state.len() - 1]) {
+            Err(StreebogError::InvalidLength { expected, actual }) => {
+                assert_eq!((expected, actual), (state.len(), state.len() - 1))
+            }
+            _ => panic!("truncated state accepted"),
+        }
+        assert!(StreebogHasher256::restore_state(&state[..10]).is_err());
+    }
+
+    #[test]
+    fn test_default() {
+        let mut hasher = StreebogHasher256::default();
+        hasher.update(data_1);
         hasher.finish();
-        let result = hasher.get_result();
-        assert_eq!(&result[..], &should_be[..]);
+        let mut should_be = StreebogHasher256::new();
+        should_be.update(data_1);
+        should_be.finish();
+        assert_eq!(hasher.result, should_be.result);
+        assert_eq!(hasher.result[..2], [0x00, 0x55]);
+
+        #[derive(Default)]
+        struct Holder {
+            hasher: Option<StreebogHasher512>,
+        }
+        let mut holder = Holder::default();
+        let hasher = holder.hasher.get_or_insert_with(Default::default);
+        hasher.update(data_1);
+        hasher.finish();
+        assert_eq!(hasher.result, hash512(data_1));
     }
 
     #[test]
-    fn test_pad_data() {
-        let mut data = Vec::new();
-        data.extend_from_slice(data_1);
-        let should_be: [u8; 64] =
-            [0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33,
-             0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37,
-             0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31,
-             0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35,
-             0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x01];
-        assert_eq!(&super::pad_data(data)[..], &should_be[..]);
+    fn test_last_block_len_63() {
+        // One full block and partial block of 63 bytes (504 bits, which doesn't fit into one byte)
+        let data: Vec<u8> = (0..127u32).map(|i| (i * 3) as u8).collect();
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(&data);
+        hasher.finish();
+        assert_eq!(hasher.to_hex(),
+                   "af1b5d9e40d5c9e257bb7e8606e6aaecd32ac954a5939ef02aedd0705b0353baf6dc50de6ac82a7\
+                    ee2154a840bf0d203cf5c49295b2742e9b456cdca4bf9a50e");
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(&data);
+        hasher.finish();
+        assert_eq!(hasher.to_hex(),
+                   "7cbeeb845a3c3740cd3cb0feddcb92657882bb37e63d36b116a3be480d8b9dbd");
+    }
+
+    // Splits data at given offsets (taken modulo data length) and feeds pieces to hasher
+    fn hash512_chunked(data: &[u8], offsets: &[usize]) -> [u8; 64] {
+        let mut offsets: Vec<usize> = offsets.iter().map(|i| i % (data.len() + 1)).collect();
+        offsets.sort();
+        let mut hasher = StreebogHasher512::new();
+        let mut start = 0;
+        for offset in offsets {
+            hasher.update(&data[start..offset]);
+            start = offset;
+        }
+        hasher.update(&data[start..]);
+        hasher.finish();
+        hasher.result
+    }
+
+    use proptest::collection::vec;
+    use proptest::prelude::any;
+
+    proptest! {
+        // Up to 600 bytes (several blocks) split into up to 20 pieces, including empty ones
+        #[test]
+        fn prop_chunking_doesnt_matter(data in vec(any::<u8>(), 0..600),
+                                       offsets in vec(any::<usize>(), 0..20)) {
+            prop_assert_eq!(hash512_chunked(&data, &offsets).to_vec(), hash512(&data).to_vec());
+        }
+    }
+
+    #[test]
+    fn test_empty_input_chunked() {
+        // Empty updates are no-op, digest of empty input must never change (big-endian byte order)
+        assert_eq!(hash512_chunked(b"", &[0, 0, 0])[..], hash512(b"")[..]);
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(b"");
+        hasher.finish();
+        assert_eq!(hasher.to_hex(),
+                   "8a1a1c4cbf909f8ecb81cd1b5c713abad26a4cac2a5fda3ce86e352855712f36a7f0be98eb6cf51\
+                    553b507b73a87e97946aebc29859255049f86aa09a25d948e");
+    }
+
+    #[test]
+    fn test_empty_message() {
+        // Published digests of empty message are in byte-string order, i.e. reversed comparing to
+        // `get_result`
+        let mut digest = hash512(b"");
+        digest.reverse();
+        assert_eq!(digest[..],
+                   [0x8e, 0x94, 0x5d, 0xa2, 0x09, 0xaa, 0x86, 0x9f, 0x04, 0x55, 0x92, 0x85, 0x29,
+                    0xbc, 0xae, 0x46, 0x79, 0xe9, 0x87, 0x3a, 0xb7, 0x07, 0xb5, 0x53, 0x15, 0xf5,
+                    0x6c, 0xeb, 0x98, 0xbe, 0xf0, 0xa7, 0x36, 0x2f, 0x71, 0x55, 0x28, 0x35, 0x6e,
+                    0xe8, 0x3c, 0xda, 0x5f, 0x2a, 0xac, 0x4c, 0x6a, 0xd2, 0xba, 0x3a, 0x71, 0x5c,
+                    0x1b, 0xcd, 0x81, 0xcb, 0x8e, 0x9f, 0x90, 0xbf, 0x4c, 0x1c, 0x1a, 0x8a][..]);
+
+        let mut digest = hash256(b"");
+        digest.reverse();
+        assert_eq!(digest,
+                   [0x3f, 0x53, 0x9a, 0x21, 0x3e, 0x97, 0xc8, 0x02, 0xcc, 0x22, 0x9d, 0x47, 0x4c,
+                    0x6a, 0xa3, 0x2a, 0x82, 0x5a, 0x36, 0x0b, 0x2a, 0x93, 0x3a, 0x94, 0x9f, 0xd9,
+                    0x25, 0x20, 0x8d, 0x9c, 0xe1, 0xbb]);
+    }
+
+    // Implementor of the trait outside of crate, which defines only methods without default
+    // implementation
+    struct ExternalHasher(StreebogHasher512);
+
+    impl StreebogHasher for ExternalHasher {
+        fn new() -> ExternalHasher {
+            ExternalHasher(StreebogHasher512::new())
+        }
+
+        fn update(&mut self, data_chunk: &[u8]) {
+            self.0.update(data_chunk);
+        }
+
+        fn finish(&mut self) {
+            self.0.finish();
+        }
+
+        fn get_result(&self) -> Box<[u8]> {
+            self.0.get_result()
+        }
+
+        fn get_result_str(&self) -> String {
+            self.0.get_result_str()
+        }
+
+        fn reset(&mut self) {
+            self.0.reset();
+        }
+    }
+
+    #[test]
+    fn test_default_trait_methods() {
+        let mut hasher = ExternalHasher::new();
+        assert!(hasher.try_update(data_1).is_ok());
+        assert!(hasher.try_finish().is_ok());
+        assert_eq!(hasher.get_result()[..], hash512(data_1)[..]);
+
+        let mut out = [0u8; 64];
+        assert!(matches!(hasher.get_result_into(&mut out[..63]),
+                         Err(StreebogError::InvalidLength { expected: 64, actual: 63 })));
+        assert_eq!(hasher.get_result_into(&mut out).unwrap(), 64);
+        assert_eq!(out[..], hash512(data_1)[..]);
+        hasher.reset();
+        assert!(matches!(hasher.get_result_into(&mut out), Err(StreebogError::InvalidState)));
     }
 }
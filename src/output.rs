@@ -0,0 +1,250 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+use core::ops::{BitXor, Deref};
+
+#[cfg(feature = "alloc")]
+use fingerprint_hex;
+use {StreebogHasher256, StreebogHasher512};
+
+/// Digest of Streebog-512 (bytes are in big-endian order, as returned by `get_result`).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Streebog512Digest(pub [u8; 64]);
+
+impl Streebog512Digest {
+    /// Returns first `bytes` bytes of digest as lowercase hex without `0x` prefix (see
+    /// `StreebogHasher512::fingerprint`).
+    ///
+    /// **Note!** Short fingerprint is intended only for display, it is not cryptographically
+    /// meaningful.
+    #[cfg(feature = "alloc")]
+    pub fn fingerprint(&self, bytes: usize) -> String {
+        fingerprint_hex(&self.0, bytes)
+    }
+}
+
+/// Finishes hashing (the same as `StreebogHasher512::finalize`).
+impl From<StreebogHasher512> for Streebog512Digest {
+    fn from(hasher: StreebogHasher512) -> Streebog512Digest {
+        hasher.finalize()
+    }
+}
+
+/// Element-wise XOR of two digests (e.g. for accumulators, which fold many digests together).
+///
+/// **Note!** XOR-folding is not collision-resistant on its own: e.g. digests can be reordered,
+/// and any digest included twice cancels out.
+impl BitXor for Streebog512Digest {
+    type Output = Streebog512Digest;
+
+    fn bitxor(self, rhs: Streebog512Digest) -> Streebog512Digest {
+        let mut result = self.0;
+        for i in result.iter_mut().zip(rhs.0.iter()) {
+            *i.0 ^= *i.1;
+        }
+        Streebog512Digest(result)
+    }
+}
+
+/// Digest of Streebog-256 (bytes are in big-endian order, as returned by `get_result`).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Streebog256Digest(pub [u8; 32]);
+
+impl Streebog256Digest {
+    /// Returns first `bytes` bytes of digest as lowercase hex without `0x` prefix (see
+    /// `StreebogHasher256::fingerprint`).
+    ///
+    /// **Note!** Short fingerprint is intended only for display, it is not cryptographically
+    /// meaningful.
+    #[cfg(feature = "alloc")]
+    pub fn fingerprint(&self, bytes: usize) -> String {
+        fingerprint_hex(&self.0, bytes)
+    }
+}
+
+/// Finishes hashing (the same as `StreebogHasher256::finalize`).
+impl From<StreebogHasher256> for Streebog256Digest {
+    fn from(hasher: StreebogHasher256) -> Streebog256Digest {
+        hasher.finalize()
+    }
+}
+
+/// Element-wise XOR of two digests (e.g. for accumulators, which fold many digests together).
+///
+/// **Note!** XOR-folding is not collision-resistant on its own: e.g. digests can be reordered,
+/// and any digest included twice cancels out.
+impl BitXor for Streebog256Digest {
+    type Output = Streebog256Digest;
+
+    fn bitxor(self, rhs: Streebog256Digest) -> Streebog256Digest {
+        let mut result = self.0;
+        for i in result.iter_mut().zip(rhs.0.iter()) {
+            *i.0 ^= *i.1;
+        }
+        Streebog256Digest(result)
+    }
+}
+
+// Writes digest as hex with respect to width, fill and the other flags of formatter (`0x` prefix
+// is added with `#` flag)
+fn fmt_hex(data: &[u8], uppercase: bool, f: &mut fmt::Formatter) -> fmt::Result {
+    let digits = if uppercase {
+        b"0123456789ABCDEF"
+    } else {
+        b"0123456789abcdef"
+    };
+    let mut buffer = [0u8; 128];
+    for i in buffer.chunks_mut(2).zip(data.iter()) {
+        i.0[0] = digits[(*i.1 >> 4) as usize];
+        i.0[1] = digits[(*i.1 & 0xf) as usize];
+    }
+    let hex = ::core::str::from_utf8(&buffer[..data.len() * 2]).map_err(|_| fmt::Error)?;
+    f.pad_integral(true, "0x", hex)
+}
+
+macro_rules! impl_fmt {
+    ($digest:ident, $name:expr) => {
+        impl fmt::LowerHex for $digest {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt_hex(&self.0, false, f)
+            }
+        }
+
+        impl fmt::UpperHex for $digest {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt_hex(&self.0, true, f)
+            }
+        }
+
+        /// Lowercase hex without `0x` prefix (the same as `{:x}`).
+        impl fmt::Display for $digest {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::LowerHex::fmt(self, f)
+            }
+        }
+
+        impl fmt::Debug for $digest {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, concat!($name, "({:x})"), self)
+            }
+        }
+    };
+}
+
+impl_fmt!(Streebog512Digest, "Streebog512");
+impl_fmt!(Streebog256Digest, "Streebog256");
+
+macro_rules! impl_bytes {
+    ($digest:ident, $size:expr) => {
+        impl AsRef<[u8]> for $digest {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl Deref for $digest {
+            type Target = [u8];
+
+            fn deref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl From<$digest> for [u8; $size] {
+            fn from(digest: $digest) -> [u8; $size] {
+                digest.0
+            }
+        }
+    };
+}
+
+impl_bytes!(Streebog512Digest, 64);
+impl_bytes!(Streebog256Digest, 32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+    use {hash256, hash512, StreebogHasher};
+
+    #[test]
+    fn test_bitxor() {
+        let a = Streebog512Digest(hash512(b"a"));
+        let b = Streebog512Digest(hash512(b"b"));
+        assert_eq!(a ^ b ^ b, a);
+        assert_eq!(a ^ b, b ^ a);
+        assert_eq!((a ^ a).0[..], [0u8; 64][..]);
+
+        let a = Streebog256Digest(hash256(b"a"));
+        let b = Streebog256Digest(hash256(b"b"));
+        assert_eq!(a ^ b ^ b, a);
+        assert_eq!((a ^ a).0, [0u8; 32]);
+        assert_eq!((a ^ b).0[0], a.0[0] ^ b.0[0]);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let digest = Streebog256Digest(hash256(b"0123456789012345678901234567890123456789\
+                                                      01234567890123456789012"));
+        assert_eq!(digest.fingerprint(4), "00557be5");
+        assert_eq!(Streebog512Digest([0xabu8; 64]).fingerprint(2), "abab");
+    }
+
+    #[test]
+    fn test_from_hasher() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(b"012345678901234567890123456789012345678901234567890123456789012");
+        let digest: Streebog512Digest = hasher.into();
+        assert_eq!(digest.fingerprint(64),
+                   "486f64c1917879417fef082b3381a4e211c324f074654c38823a7b76f830ad00fa1fbae42b1285c\
+                    0352f227524bc9ab16254288dd6863dccd5b9f54a1ad0541b");
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(b"012345678901234567890123456789012345678901234567890123456789012");
+        hasher.finish();
+        let digest: Streebog256Digest = hasher.into();
+        assert_eq!(digest.fingerprint(32),
+                   "00557be5e584fd52a449b16b0251d05d27f94ab76cbaa6da890b59d8ef1e159d");
+    }
+
+    #[test]
+    fn test_fmt() {
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(b"012345678901234567890123456789012345678901234567890123456789012");
+        hasher.finish();
+        let hex = "00557be5e584fd52a449b16b0251d05d27f94ab76cbaa6da890b59d8ef1e159d";
+        let digest: Streebog256Digest = hasher.into();
+        assert_eq!(format!("{:x}", digest), hex);
+        assert_eq!(format!("{:X}", digest), hex.to_uppercase());
+        assert_eq!(format!("{:064x}", digest), hex);
+        assert_eq!(format!("{:066x}", digest), format!("00{}", hex));
+        assert_eq!(format!("{:#x}", digest), format!("0x{}", hex));
+        assert_eq!(format!("{:>66}", digest), format!("  {}", hex));
+        assert_eq!(digest.to_string(), hex);
+        assert_eq!(format!("{:?}", digest), format!("Streebog256({})", hex));
+
+        let digest = Streebog512Digest([0xabu8; 64]);
+        assert_eq!(format!("{:X}", digest), "AB".repeat(64));
+        assert_eq!(format!("{:?}", digest), format!("Streebog512({})", "ab".repeat(64)));
+    }
+
+    #[test]
+    fn test_bytes() {
+        let data = b"012345678901234567890123456789012345678901234567890123456789012";
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data);
+        let digest = hasher.finalize();
+        assert_eq!(digest.as_ref(), &hash512(data)[..]);
+        assert_eq!(digest.len(), 64);
+        assert_eq!(digest[0], 0x48);
+        let bytes: [u8; 64] = digest.into();
+        assert_eq!(bytes[..], hash512(data)[..]);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data);
+        let digest = hasher.finalize();
+        assert_eq!(digest.as_ref(), &hash256(data)[..]);
+        assert_eq!(&digest[..2], &[0x00, 0x55]);
+        assert_eq!(<[u8; 32]>::from(digest), hash256(data));
+    }
+}
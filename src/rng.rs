@@ -0,0 +1,116 @@
+#[cfg(feature = "rand")]
+use rand_core::{self, impls, RngCore};
+
+use alloc::vec::Vec;
+
+use mgf1_block512;
+
+/// A deterministic generator of byte stream from seed (e.g. to produce reproducible test data).
+///
+/// Output is the same as of `mgf1_streebog512` with the same seed, i.e. concatenation of
+/// `H(seed || C)` for 32-bit big-endian counter `C` starting from 0. Stream repeats after
+/// `2^32 * 64` bytes (counter wraps around).
+///
+/// **Note!** This generator is fully determined by seed and is intended for tests and tooling,
+/// not for generation of keys or nonces: it has no reseeding, no forward secrecy and output is
+/// only as unpredictable as seed.
+///
+/// # Examples
+///
+/// ```
+/// use streebog_hash::*;
+/// let mut rng = StreebogRng::new(b"seed");
+/// let mut data = [0u8; 100];
+/// rng.fill_bytes(&mut data);
+/// assert_eq!(&data[..], &mgf1_streebog512(b"seed", 100)[..]);
+/// ```
+pub struct StreebogRng {
+    seed: Vec<u8>,
+    counter: u32,
+    block: [u8; 64],
+    // Position of first unused byte in block
+    pos: usize,
+}
+
+impl StreebogRng {
+    /// Creates new generator from seed.
+    pub fn new(seed: &[u8]) -> StreebogRng {
+        StreebogRng {
+            seed: seed.to_vec(),
+            counter: 0,
+            block: [0u8; 64],
+            pos: 64,
+        }
+    }
+
+    /// Fills `dst` with next bytes of stream.
+    pub fn fill_bytes(&mut self, dst: &mut [u8]) {
+        let mut filled = 0;
+        while filled < dst.len() {
+            if self.pos == 64 {
+                self.block = mgf1_block512(&self.seed, self.counter);
+                self.counter = self.counter.wrapping_add(1);
+                self.pos = 0;
+            }
+            let len = ::core::cmp::min(dst.len() - filled, 64 - self.pos);
+            dst[filled..filled + len].copy_from_slice(&self.block[self.pos..self.pos + len]);
+            filled += len;
+            self.pos += len;
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl RngCore for StreebogRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        StreebogRng::fill_bytes(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        StreebogRng::fill_bytes(self, dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mgf1_streebog512;
+
+    #[test]
+    fn test_streebog_rng() {
+        let mut rng_1 = StreebogRng::new(b"seed");
+        let mut rng_2 = StreebogRng::new(b"seed");
+        let mut data_1 = [0u8; 300];
+        let mut data_2 = [0u8; 300];
+        rng_1.fill_bytes(&mut data_1);
+        // Different chunking must give the same stream
+        rng_2.fill_bytes(&mut data_2[..1]);
+        rng_2.fill_bytes(&mut data_2[1..64]);
+        rng_2.fill_bytes(&mut data_2[64..200]);
+        rng_2.fill_bytes(&mut data_2[200..]);
+        assert_eq!(&data_1[..], &data_2[..]);
+        assert_eq!(&data_1[..], &mgf1_streebog512(b"seed", 300)[..]);
+
+        let mut rng_3 = StreebogRng::new(b"other seed");
+        rng_3.fill_bytes(&mut data_2);
+        assert!(data_1[..] != data_2[..]);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_rng_core() {
+        let mut rng = StreebogRng::new(b"seed");
+        let expected = mgf1_streebog512(b"seed", 12);
+        assert_eq!(RngCore::next_u32(&mut rng).to_le_bytes()[..], expected[..4]);
+        assert_eq!(RngCore::next_u64(&mut rng).to_le_bytes()[..], expected[4..12]);
+    }
+}
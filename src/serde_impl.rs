@@ -0,0 +1,179 @@
+// Serialization of 64-byte arrays for `serde` feature (serde implements traits only for arrays of
+// up to 32 elements), used with `#[serde(with = "serde_impl")]`, and checked deserialization of
+// hashers
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::convert::TryFrom;
+use core::fmt;
+
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serializer};
+
+use {StreebogError, StreebogHasher256, StreebogHasher512, StreebogHasherCtx};
+
+pub fn serialize<S: Serializer>(data: &[u8; 64], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(data)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 64], D::Error> {
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = [u8; 64];
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "64 bytes")
+        }
+
+        fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<[u8; 64], E> {
+            if v.len() != 64 {
+                return Err(E::invalid_length(v.len(), &self));
+            }
+            let mut result = [0u8; 64];
+            result.copy_from_slice(v);
+            Ok(result)
+        }
+
+        // Formats without native byte strings (e.g. JSON) give sequence of numbers
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[u8; 64], A::Error> {
+            let mut result = [0u8; 64];
+            for (i, byte) in result.iter_mut().enumerate() {
+                *byte = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i, &self))?;
+            }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(A::Error::invalid_length(65, &self));
+            }
+            Ok(result)
+        }
+    }
+
+    deserializer.deserialize_bytes(BytesVisitor)
+}
+
+// Serialized state may come from outside (e.g. resumed upload), so it is checked the same way as
+// in `restore_state` before it becomes hasher, otherwise next `update` may panic
+fn check_state(ctx: &StreebogHasherCtx, prefix_ctx: Option<&StreebogHasherCtx>, is_finished: bool,
+               max_len: u64, len_exceeded: bool)
+               -> Result<(), StreebogError> {
+    let ctx_is_valid = |ctx: &StreebogHasherCtx| ctx.data_len < 64 && ctx.absorbed <= max_len;
+    if !ctx_is_valid(ctx) || !prefix_ctx.is_none_or(ctx_is_valid) ||
+       (is_finished && len_exceeded) {
+        return Err(StreebogError::InvalidState);
+    }
+    Ok(())
+}
+
+// Unchecked mirror of hasher, which is deserialized first and then converted with `check_state`
+macro_rules! impl_checked_deserialize {
+    ($state:ident, $hasher:ident, $size:expr $(, #[$result_attr:meta])*) => {
+        #[derive(Deserialize)]
+        pub struct $state {
+            ctx: StreebogHasherCtx,
+            is_finished: bool,
+            $(#[$result_attr])*
+            result: [u8; $size],
+            #[cfg(feature = "alloc")]
+            prefix_ctx: Option<Box<StreebogHasherCtx>>,
+            max_len: u64,
+            len_exceeded: bool,
+        }
+
+        impl TryFrom<$state> for $hasher {
+            type Error = StreebogError;
+
+            fn try_from(state: $state) -> Result<$hasher, StreebogError> {
+                #[cfg(feature = "alloc")]
+                let prefix_ctx = state.prefix_ctx.as_ref().map(|ctx| &**ctx);
+                #[cfg(not(feature = "alloc"))]
+                let prefix_ctx = None;
+                check_state(&state.ctx,
+                            prefix_ctx,
+                            state.is_finished,
+                            state.max_len,
+                            state.len_exceeded)?;
+                Ok($hasher {
+                    ctx: state.ctx,
+                    is_finished: state.is_finished,
+                    result: state.result,
+                    #[cfg(feature = "alloc")]
+                    prefix_ctx: state.prefix_ctx,
+                    max_len: state.max_len,
+                    len_exceeded: state.len_exceeded,
+                })
+            }
+        }
+    };
+}
+
+impl_checked_deserialize!(HasherState512, StreebogHasher512, 64, #[serde(with = "self")]);
+impl_checked_deserialize!(HasherState256, StreebogHasher256, 32);
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use {hash256, hash512, StreebogHasher, StreebogHasher256, StreebogHasher512};
+
+    static data_2_part_1: &[u8] = &[0xd1, 0xe5, 0x20, 0xe2, 0xe5, 0xf2, 0xf0, 0xe8, 0x2c, 0x20,
+                                    0xd1, 0xf2, 0xf0, 0xe8, 0xe1, 0xee, 0xe6, 0xe8, 0x20, 0xe2,
+                                    0xed, 0xf3, 0xf6, 0xe8, 0x2c, 0x20, 0xe2, 0xe5, 0xfe, 0xf2,
+                                    0xfa, 0x20, 0xf1, 0x20, 0xec, 0xee, 0xf0];
+    static data_2_part_2: &[u8] = &[0xff, 0x20, 0xf1, 0xf2, 0xf0, 0xe5, 0xeb, 0xe0, 0xec, 0xe8,
+                                    0x20, 0xed, 0xe0, 0x20, 0xf5, 0xf0, 0xe0, 0xe1, 0xf0, 0xfb,
+                                    0xff, 0x20, 0xef, 0xeb, 0xfa, 0xea, 0xfb, 0x20, 0xc8, 0xe3,
+                                    0xee, 0xf0, 0xe5, 0xe2, 0xfb];
+
+    #[test]
+    fn test_serde_round_trip() {
+        let data_2 = [data_2_part_1, data_2_part_2].concat();
+
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(data_2_part_1);
+        let json = serde_json::to_string(&hasher).unwrap();
+        let mut hasher: StreebogHasher512 = serde_json::from_str(&json).unwrap();
+        hasher.update(data_2_part_2);
+        hasher.finish();
+        assert_eq!(hasher.result[..], hash512(&data_2)[..]);
+        assert_eq!(hasher.result[..4], [0x28, 0xfb, 0xc9, 0xba]);
+
+        let mut hasher = StreebogHasher256::new();
+        hasher.update(data_2_part_1);
+        let json = serde_json::to_string(&hasher).unwrap();
+        let mut hasher: StreebogHasher256 = serde_json::from_str(&json).unwrap();
+        hasher.update(data_2_part_2);
+        hasher.finish();
+        assert_eq!(hasher.result, hash256(&data_2));
+
+        // Finished hasher keeps its result
+        let json = serde_json::to_string(&hasher).unwrap();
+        let restored: StreebogHasher256 = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.get_result(), hasher.get_result());
+
+        let truncated = json.replacen("[", "[0,", 1);
+        assert!(serde_json::from_str::<StreebogHasher256>(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_serde_invalid_state() {
+        let mut hasher = StreebogHasher512::new();
+        hasher.update(b"abc");
+        let json = serde_json::to_string(&hasher).unwrap();
+        assert!(json.contains("\"data_len\":3"));
+        for data_len in ["63", "64", "100"].iter() {
+            let tampered = json.replace("\"data_len\":3", &format!("\"data_len\":{}", data_len));
+            let result = serde_json::from_str::<StreebogHasher512>(&tampered);
+            assert_eq!(result.is_ok(), *data_len == "63", "data_len {}", data_len);
+        }
+
+        let mut hasher = StreebogHasher256::with_max_len(10);
+        hasher.update(b"abc");
+        let json = serde_json::to_string(&hasher).unwrap();
+        let tampered = json.replace("\"absorbed\":3", "\"absorbed\":11");
+        assert_ne!(tampered, json);
+        assert!(serde_json::from_str::<StreebogHasher256>(&tampered).is_err());
+        let tampered = json.replace("\"is_finished\":false", "\"is_finished\":true")
+            .replace("\"len_exceeded\":false", "\"len_exceeded\":true");
+        assert!(serde_json::from_str::<StreebogHasher256>(&tampered).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use core::hash::Hasher;
+#[cfg(test)]
+use alloc::vec::Vec;
+
+use {StreebogHasher, StreebogHasher256};
+
+/// An adapter, which implements `std::hash::Hasher` with Streebog-256 (e.g. for `HashMap` with
+/// bucketing, which is stable across platforms and languages).
+///
+/// `finish` reduces digest to `u64` by taking its low 64 bits, i.e. the last 8 bytes of digest in
+/// big-endian byte order (as returned by `get_result`), interpreted as big-endian integer.
+///
+/// **Note!** Result is truncated to 64 bits, so it is intended only for hash maps and similar
+/// bucketing, not as cryptographic identity of data (collisions of 64-bit values are feasible).
+/// It is also unkeyed and deterministic, so unlike default `RandomState` it doesn't protect
+/// `HashMap` from HashDoS by untrusted keys. It is much slower than usual hash map hashers too.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::hash::BuildHasherDefault;
+/// use streebog_hash::StreebogStdHasher;
+/// let mut map: HashMap<&str, u32, BuildHasherDefault<StreebogStdHasher>> = HashMap::default();
+/// map.insert("key", 1);
+/// ```
+#[derive(Default)]
+pub struct StreebogStdHasher {
+    hasher: StreebogHasher256,
+}
+
+impl Hasher for StreebogStdHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hasher = self.hasher.clone();
+        hasher.finish();
+        let mut low_bytes = [0u8; 8];
+        low_bytes.copy_from_slice(&hasher.result[24..32]);
+        u64::from_be_bytes(low_bytes)
+    }
+}
+
+// Number of values among `finish` of integers 0..count, which fall in each of `2^bits` buckets by
+// their low bits
+#[cfg(test)]
+pub(crate) fn bucket_distribution(count: u64, bits: u32) -> Vec<u64> {
+    let mut buckets = vec![0u64; 1 << bits];
+    for i in 0..count {
+        let mut hasher = StreebogStdHasher::default();
+        hasher.write_u64(i);
+        buckets[(hasher.finish() & ((1 << bits) - 1)) as usize] += 1;
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finish_reduction() {
+        let mut hasher = StreebogStdHasher::default();
+        hasher.write(b"abc");
+        let mut std_hasher = StreebogHasher256::new();
+        std_hasher.update(b"abc");
+        std_hasher.finish();
+        let digest = std_hasher.get_result();
+        let mut expected = 0u64;
+        for i in digest[24..].iter() {
+            expected = (expected << 8) | *i as u64;
+        }
+        assert_eq!(hasher.finish(), expected);
+        // finish doesn't change state
+        assert_eq!(hasher.finish(), expected);
+    }
+
+    #[test]
+    fn test_bucket_distribution() {
+        // 4096 values in 16 buckets, 256 expected in each
+        for count in bucket_distribution(4096, 4).iter() {
+            assert!(*count > 192 && *count < 320, "bad distribution: {}", count);
+        }
+
+        // Every bit of result must change
+        let mut all_ones = u64::MAX;
+        let mut any_ones = 0u64;
+        for i in 0..64u64 {
+            let mut hasher = StreebogStdHasher::default();
+            hasher.write_u64(i);
+            all_ones &= hasher.finish();
+            any_ones |= hasher.finish();
+        }
+        assert_eq!((all_ones, any_ones), (0, u64::MAX));
+    }
+
+    #[test]
+    fn test_stable_value() {
+        // Must never change between runs, platforms and versions
+        let mut hasher = StreebogStdHasher::default();
+        hasher.write(b"stable");
+        assert_eq!(hasher.finish(), 0x6544e2c5dc69ab67);
+
+        let mut hasher = StreebogStdHasher::default();
+        hasher.write(b"sta");
+        hasher.write(b"ble");
+        assert_eq!(hasher.finish(), 0x6544e2c5dc69ab67);
+    }
+}
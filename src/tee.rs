@@ -0,0 +1,198 @@
+use std::boxed::Box;
+use std::io::{self, Write};
+use std::vec::Vec;
+
+use {StreebogHasher, StreebogHasher256, StreebogHasher512};
+
+/// A writer, which hashes all data written through it and forwards it to inner writer, so data can
+/// be hashed and persisted in one pass.
+///
+/// Only bytes accepted by inner writer are hashed. If hasher rejects them (it is finished or its
+/// length limit is exceeded, see `StreebogHasher::try_update`), `write` returns error, because
+/// these bytes are already written, but not hashed.
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+/// use streebog_hash::*;
+/// let mut tee = TeeHasher::new(Vec::new(), StreebogHasher512::new());
+/// io::copy(&mut &[0xfcu8; 128][..], &mut tee).unwrap();
+/// let result = tee.finish_digest();
+/// let (data, _) = tee.into_inner();
+/// ```
+pub struct TeeHasher<W: Write, H: StreebogHasher = StreebogHasher512> {
+    writer: W,
+    hasher: H,
+}
+
+impl<W: Write, H: StreebogHasher> TeeHasher<W, H> {
+    /// Creates new tee from a writer and a hasher.
+    pub fn new(writer: W, hasher: H) -> TeeHasher<W, H> {
+        TeeHasher { writer, hasher }
+    }
+
+    /// Finishes hasher and returns result of hashing (see `StreebogHasher::get_result`).
+    ///
+    /// Inner writer is not flushed.
+    pub fn finish_digest(&mut self) -> Box<[u8]> {
+        self.hasher.finish();
+        self.hasher.get_result()
+    }
+
+    /// Returns reference to inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns inner writer and hasher.
+    pub fn into_inner(self) -> (W, H) {
+        (self.writer, self.hasher)
+    }
+}
+
+impl<W: Write, H: StreebogHasher> Write for TeeHasher<W, H> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.hasher.try_update(&buf[..written]).map_err(io::Error::other)?;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// A hasher, which also keeps copy of all data written into it (e.g. to send data again, if
+/// verification of digest fails).
+///
+/// **Note!** It retains whole input in memory, so it must be used only when data is known to be
+/// small enough. Use plain hasher or `TeeHasher`, if copy is not needed or can be written
+/// elsewhere.
+///
+/// # Examples
+///
+/// ```
+/// use streebog_hash::*;
+/// let mut hasher = CapturingHasher::new(StreebogHasher256::new());
+/// hasher.update(b"some data");
+/// let (digest, data) = hasher.into_parts();
+/// assert_eq!(&data[..], b"some data");
+/// ```
+pub struct CapturingHasher<H: StreebogHasher = StreebogHasher512> {
+    hasher: H,
+    captured: Vec<u8>,
+}
+
+impl<H: StreebogHasher> CapturingHasher<H> {
+    /// Creates new capturing hasher from a hasher.
+    pub fn new(hasher: H) -> CapturingHasher<H> {
+        CapturingHasher {
+            hasher,
+            captured: Vec::new(),
+        }
+    }
+
+    /// Writes some data into hasher and appends it to captured data.
+    ///
+    /// Data rejected by hasher (if it is finished or length limit is exceeded, see
+    /// `StreebogHasher::try_update`) is not captured, so captured data is always exactly the input
+    /// of digest.
+    pub fn update(&mut self, data_chunk: &[u8]) {
+        if self.hasher.try_update(data_chunk).is_ok() {
+            self.captured.extend_from_slice(data_chunk);
+        }
+    }
+
+    /// Returns data captured so far.
+    pub fn captured(&self) -> &[u8] {
+        &self.captured
+    }
+
+    /// Finishes hasher and returns result of hashing (see `StreebogHasher::get_result`) and all
+    /// captured data.
+    ///
+    /// It works with any hasher, so digest is returned as `Box<[u8]>`. Use `into_parts` of
+    /// `CapturingHasher<StreebogHasher512>` or `CapturingHasher<StreebogHasher256>` to get array.
+    pub fn into_parts_boxed(mut self) -> (Box<[u8]>, Vec<u8>) {
+        self.hasher.finish();
+        (self.hasher.get_result(), self.captured)
+    }
+}
+
+impl CapturingHasher<StreebogHasher512> {
+    /// Finishes hasher and returns digest (see `StreebogHasher512::finalize`) and all captured
+    /// data.
+    pub fn into_parts(self) -> ([u8; 64], Vec<u8>) {
+        (self.hasher.finalize().0, self.captured)
+    }
+}
+
+impl CapturingHasher<StreebogHasher256> {
+    /// Finishes hasher and returns digest (see `StreebogHasher256::finalize`) and all captured
+    /// data.
+    pub fn into_parts(self) -> ([u8; 32], Vec<u8>) {
+        (self.hasher.finalize().0, self.captured)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use {hash256, hash512};
+
+    #[test]
+    fn test_tee_hasher() {
+        let data = [0xfcu8; 200];
+        let mut tee = TeeHasher::new(Vec::new(), StreebogHasher256::new());
+        io::copy(&mut &data[..], &mut tee).unwrap();
+        let result = tee.finish_digest();
+        let (copied, _) = tee.into_inner();
+        assert_eq!(&copied[..], &data[..]);
+        assert_eq!(result[..], hash256(&data)[..]);
+
+        // Bytes, which are written, but not hashed, give error
+        let mut tee = TeeHasher::new(Vec::new(), StreebogHasher256::with_max_len(3));
+        tee.write_all(b"abc").unwrap();
+        assert!(tee.write_all(b"d").is_err());
+    }
+
+    #[test]
+    fn test_capturing_hasher() {
+        let mut hasher = CapturingHasher::new(StreebogHasher512::new());
+        hasher.update(&[0xfcu8; 100]);
+        hasher.update(b"");
+        hasher.update(b"tail");
+        assert_eq!(hasher.captured().len(), 104);
+        let (result, captured) = hasher.into_parts();
+
+        let mut data = vec![0xfcu8; 100];
+        data.extend_from_slice(b"tail");
+        assert_eq!(captured, data);
+        assert_eq!(result[..], hash512(&data)[..]);
+
+        let mut hasher = CapturingHasher::new(StreebogHasher256::new());
+        hasher.update(b"some data");
+        let (result, captured) = hasher.into_parts();
+        assert_eq!((result, &captured[..]), (hash256(b"some data"), &b"some data"[..]));
+
+        // Generic hasher gives boxed digest
+        let mut hasher = CapturingHasher::new(StreebogHasher256::new());
+        hasher.update(b"some data");
+        let (result, _) = hasher.into_parts_boxed();
+        assert_eq!(result[..], hash256(b"some data")[..]);
+
+        // Data rejected by hasher is not captured
+        let mut hasher = CapturingHasher::new(StreebogHasher256::with_max_len(5));
+        hasher.update(b"abc");
+        hasher.update(b"defg");
+        assert_eq!(hasher.captured(), b"abc");
+        let mut finished = StreebogHasher256::new();
+        finished.finish();
+        let mut hasher = CapturingHasher::new(finished);
+        hasher.update(b"abc");
+        assert!(hasher.captured().is_empty());
+    }
+}
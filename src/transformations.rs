@@ -1,104 +1,227 @@
-#![allow(mutable_transmutes)]
+use core::convert::TryInto;
+#[cfg(all(feature = "runtime-tables", not(any(feature = "profile-size", feature = "ct"))))]
+use std::sync::OnceLock;
 
-use std::slice;
+#[cfg(all(feature = "runtime-tables", not(feature = "profile-size"), not(feature = "std")))]
+compile_error!("feature `runtime-tables` requires `std` (or use `profile-size`, which has no table)");
 
 use const_data;
+#[cfg(any(not(any(feature = "runtime-tables", feature = "ct")), test))]
 use precomp_data;
 
-/*pub fn xor512(l: [u8; 64], r: [u8; 64]) -> [u8; 64] {
-    let mut result = [0 as u8; 64];
-    let ptr_result: &mut [u64; 8] = unsafe { mem::transmute(&result) };
-    let ptr_l: &[u64; 8] = unsafe { mem::transmute(&l) };
-    let ptr_r: &[u64; 8] = unsafe { mem::transmute(&r) };
-    for i in 0..8 {
-        ptr_result[i] = ptr_l[i] ^ ptr_r[i];
+// Tables of L alone are used only by tests (to check LPS_precomp), `ct` uses table-free L
+#[cfg(all(not(any(feature = "runtime-tables", feature = "profile-size", feature = "ct")), test))]
+fn A_precomp() -> &'static [[u64; 256]; 8] {
+    &precomp_data::A_precomp
+}
+
+#[cfg(all(feature = "runtime-tables", not(any(feature = "profile-size", feature = "ct")), test))]
+fn A_precomp() -> &'static [[u64; 256]; 8] {
+    static A_precomp_table: OnceLock<[[u64; 256]; 8]> = OnceLock::new();
+    A_precomp_table.get_or_init(generate_A_precomp)
+}
+
+// A_precomp[j][b] is XOR of rows of matrix A, which correspond to bits set in byte b, which is j-th
+// byte (counting from the most significant one) of 64-bit word
+#[cfg(any(all(feature = "runtime-tables", not(feature = "profile-size"), not(feature = "ct")),
+          test))]
+fn generate_A_precomp() -> [[u64; 256]; 8] {
+    let mut table = [[0u64; 256]; 8];
+    for (j, row) in table.iter_mut().enumerate() {
+        for (b, element) in row.iter_mut().enumerate() {
+            for k in 0..8 {
+                if (b & (0x1 << (7 - k))) != 0 {
+                    *element ^= const_data::A[j * 8 + k];
+                }
+            }
+        }
     }
-    result
-}*/
+    table
+}
+
+#[cfg(not(any(feature = "runtime-tables", feature = "profile-size", feature = "ct")))]
+fn LPS_precomp() -> &'static [[u64; 256]; 8] {
+    &precomp_data::LPS_precomp
+}
 
+#[cfg(all(feature = "runtime-tables", not(feature = "profile-size"), not(feature = "ct")))]
+fn LPS_precomp() -> &'static [[u64; 256]; 8] {
+    static LPS_precomp_table: OnceLock<[[u64; 256]; 8]> = OnceLock::new();
+    LPS_precomp_table.get_or_init(generate_LPS_precomp)
+}
+
+// LPS_precomp[j][b] = A_precomp[j][pi[b]], i.e. S-box is merged into table of L
+#[cfg(any(all(feature = "runtime-tables", not(feature = "profile-size"), not(feature = "ct")),
+          test))]
+fn generate_LPS_precomp() -> [[u64; 256]; 8] {
+    let A_precomp = generate_A_precomp();
+    let mut table = [[0u64; 256]; 8];
+    for (row, A_row) in table.iter_mut().zip(A_precomp.iter()) {
+        for (element, pi_b) in row.iter_mut().zip(const_data::pi.iter()) {
+            *element = A_row[*pi_b as usize];
+        }
+    }
+    table
+}
+
+// XORs by 64-bit words (byte order of words doesn't matter for XOR, so native one is used)
 pub fn xor512(l: [u8; 64], r: [u8; 64]) -> [u8; 64] {
-    let mut result = [0 as u8; 64];
-    for i in 0..64 {
-        result[i] = l[i] ^ r[i];
+    let mut result = [0u8; 64];
+    for ((res, l), r) in result.chunks_exact_mut(8).zip(l.chunks_exact(8)).zip(r.chunks_exact(8)) {
+        let word = u64::from_ne_bytes(l.try_into().unwrap()) ^
+                   u64::from_ne_bytes(r.try_into().unwrap());
+        res.copy_from_slice(&word.to_ne_bytes());
     }
     result
 }
 
+// Addition modulo 2^512 of numbers represented as little-endian byte arrays (computed by 64-bit
+// words). Hashing uses word representation directly, so it is kept only for tests
+#[cfg(test)]
 pub fn add_modulo512(l: [u8; 64], r: [u8; 64]) -> [u8; 64] {
-    let mut result = [0 as u8; 64];
-    let mut t = 0;
-    for i in 0..64 {
-        t = l[i] as i32 + r[i] as i32 + (t >> 8);
-        result[i] = t as u8;
+    words_to_bytes(add_modulo512_words(bytes_to_words(l), bytes_to_words(r)))
+}
+
+// The same as add_modulo512, but for numbers represented as little-endian arrays of 64-bit words
+pub fn add_modulo512_words(l: [u64; 8], r: [u64; 8]) -> [u64; 8] {
+    let mut result = [0u64; 8];
+    let mut carry = false;
+    for i in 0..8 {
+        let (sum, carry_1) = l[i].overflowing_add(r[i]);
+        let (sum, carry_2) = sum.overflowing_add(carry as u64);
+        result[i] = sum;
+        carry = carry_1 || carry_2;
     }
     result
 }
 
+pub fn bytes_to_words(a: [u8; 64]) -> [u64; 8] {
+    let mut result = [0u64; 8];
+    for (word, bytes) in result.iter_mut().zip(a.chunks_exact(8)) {
+        let mut word_bytes = [0u8; 8];
+        word_bytes.copy_from_slice(bytes);
+        *word = u64::from_le_bytes(word_bytes);
+    }
+    result
+}
+
+pub fn words_to_bytes(a: [u64; 8]) -> [u8; 64] {
+    let mut result = [0u8; 64];
+    for (bytes, word) in result.chunks_exact_mut(8).zip(a.iter()) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    result
+}
+
+#[cfg(any(feature = "profile-size", feature = "ct", test))]
 pub fn S(a: [u8; 64]) -> [u8; 64] {
-    let mut result = [0 as u8; 64];
+    if cfg!(feature = "ct") {
+        return S_ct(a);
+    }
+    let mut result = [0u8; 64];
     for i in 0..64 {
         result[i] = const_data::pi[a[i] as usize];
     }
     result
 }
 
+// Constant-time version of S: every byte scans the whole pi table and picks the value with mask,
+// so memory access pattern doesn't depend on data (resists cache-timing attacks)
+#[cfg(any(feature = "profile-size", feature = "ct", test))]
+pub fn S_ct(a: [u8; 64]) -> [u8; 64] {
+    let mut result = [0u8; 64];
+    for i in 0..64 {
+        let mut value = 0u8;
+        for (j, pi_j) in const_data::pi.iter().enumerate() {
+            // 0xff if j == a[i], 0x00 otherwise
+            let mask = (((j as u8 ^ a[i]) as u16).wrapping_sub(1) >> 8) as u8;
+            value |= pi_j & mask;
+        }
+        result[i] = value;
+    }
+    result
+}
+
+#[cfg(any(feature = "profile-size", feature = "ct", test))]
 pub fn P(a: [u8; 64]) -> [u8; 64] {
-    let mut result = [0 as u8; 64];
+    let mut result = [0u8; 64];
     for i in 0..64 {
         result[i] = a[const_data::tau[i] as usize];
     }
     result
 }
 
-/*pub fn L(a: [u8; 64]) -> [u8; 64] {
-    let ptr = &a[0] as *const u8 as *mut u64;
-    let mut a_u64 = unsafe { slice::from_raw_parts_mut::<u64>(ptr, 8) };
-    let i_constants: [usize; 8] = [7, 15, 23, 31, 39, 47, 55, 63];
-    for (i, a_element) in (0..8).zip(a_u64.iter_mut()) {
-        let mut temp = 0 as u64;
+// L without precomputed table: multiplication by matrix A bit by bit (for `profile-size` and
+// `ct`). Rows are selected with mask instead of branch, so it doesn't depend on data neither by
+// memory access nor by control flow
+#[cfg(any(feature = "profile-size", feature = "ct", test))]
+pub fn L_compact(a: [u8; 64]) -> [u8; 64] {
+    let mut result = [0u8; 64];
+    for (i, result_word) in result.chunks_exact_mut(8).enumerate() {
+        let mut temp = 0u64;
         for j in 0..8 {
             for k in 0..8 {
-                if (a[i_constants[i] - j] & (0x1 << (7 - k))) != 0 {
-                    temp ^= const_data::A[j * 8 + k]
-                }
+                // All ones if bit is set, zero otherwise
+                let mask = 0u64.wrapping_sub(((a[i * 8 + 7 - j] >> (7 - k)) & 0x1) as u64);
+                temp ^= const_data::A[j * 8 + k] & mask;
             }
         }
-        *a_element = temp;
+        result_word.copy_from_slice(&temp.to_le_bytes());
     }
-    a
-}*/
+    result
+}
+
+#[cfg(any(feature = "profile-size", feature = "ct"))]
 pub fn L(a: [u8; 64]) -> [u8; 64] {
-    let ptr = &a[0] as *const u8 as *mut u64;
-    let mut a_u64 = unsafe { slice::from_raw_parts_mut::<u64>(ptr, 8) };
-    let i_constants: [usize; 8] = [7, 15, 23, 31, 39, 47, 55, 63];
-    for (i, a_element) in (0..8).zip(a_u64.iter_mut()) {
-        let mut temp = 0 as u64;
-        for j in 0..8 {
-            temp ^= precomp_data::A_precomp[j][a[i_constants[i] - j] as usize];
+    L_compact(a)
+}
+
+// L with table lookups (only for tests, LPS_precomp is used instead of it)
+#[cfg(all(not(any(feature = "profile-size", feature = "ct")), test))]
+pub fn L(a: [u8; 64]) -> [u8; 64] {
+    let A_precomp = A_precomp();
+    let mut result = [0u8; 64];
+    for (i, result_word) in result.chunks_exact_mut(8).enumerate() {
+        let mut temp = 0u64;
+        for (j, row) in A_precomp.iter().enumerate() {
+            temp ^= row[a[i * 8 + 7 - j] as usize];
         }
-        *a_element = temp;
+        result_word.copy_from_slice(&temp.to_le_bytes());
     }
-    a
+    result
 }
 
-// TODO: reverse C constants in cosnt_data.rs and change _xor512 to xor512
-pub fn key_schedule(k: [u8; 64], i: usize) -> [u8; 64] {
-    // Temporary workaround, cuz in C all arrays reversed
-    fn _xor512(l: [u8; 64], r: [u8; 64]) -> [u8; 64] {
-        let mut result = [0 as u8; 64];
-        for i in 0..64 {
-            result[i] = l[i] ^ r[63 - i];
+// L(P(S(a))) in one pass with combined table: P transposes 64 bytes as 8x8 matrix, so byte j of
+// word i after P is byte i of word j
+#[cfg(not(any(feature = "profile-size", feature = "ct")))]
+pub fn LPS(a: [u8; 64]) -> [u8; 64] {
+    let LPS_precomp = LPS_precomp();
+    let mut result = [0u8; 64];
+    for (i, result_word) in result.chunks_exact_mut(8).enumerate() {
+        let mut temp = 0u64;
+        for (j, row) in LPS_precomp.iter().enumerate() {
+            temp ^= row[a[(7 - j) * 8 + i] as usize];
         }
-        result
+        result_word.copy_from_slice(&temp.to_le_bytes());
     }
-    L(P(S(_xor512(k, const_data::C[i]))))
+    result
+}
+
+// With `ct` neither S nor L may be table lookup, and `profile-size` has no tables at all
+#[cfg(any(feature = "profile-size", feature = "ct"))]
+pub fn LPS(a: [u8; 64]) -> [u8; 64] {
+    L(P(S(a)))
+}
+
+pub fn key_schedule(k: [u8; 64], i: usize) -> [u8; 64] {
+    LPS(xor512(k, const_data::C[i]))
 }
 
 pub fn E(k_init: [u8; 64], m: [u8; 64]) -> [u8; 64] {
     let mut k = k_init;
     let mut temp = xor512(k, m);
     for i in 0..12 {
-        temp = L(P(S(temp)));
+        temp = LPS(temp);
         k = key_schedule(k, i);
         temp = xor512(temp, k);
     }
@@ -107,7 +230,7 @@ pub fn E(k_init: [u8; 64], m: [u8; 64]) -> [u8; 64] {
 
 // Compression function
 pub fn g_N(N: [u8; 64], h: [u8; 64], m: [u8; 64]) -> [u8; 64] {
-    xor512(xor512(E(L(P(S(xor512(h, N)))), m), h), m)
+    xor512(xor512(E(LPS(xor512(h, N)), m), h), m)
 }
 
 #[cfg(test)]
@@ -120,34 +243,91 @@ mod tests {
                           0x36, 0x37, 0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37,
                           0x38, 0x39, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39,
                           0x30, 0x31, 0x32, 0x01];
-    static h: [u8; 64] = [0 as u8; 64];
-    static N: [u8; 64] = [0 as u8; 64];
+    static h: [u8; 64] = [0u8; 64];
+    static N: [u8; 64] = [0u8; 64];
 
     #[test]
     fn test_xor512() {
-        let l = [1 as u8; 64];
-        let r = [2 as u8; 64];
-        let should_be = [3 as u8; 64];
+        let l = [1u8; 64];
+        let r = [2u8; 64];
+        let should_be = [3u8; 64];
+        assert_eq!(&xor512(l, r)[..], &should_be[..]);
+    }
+
+    #[test]
+    fn test_xor512_asymmetric() {
+        let mut l = [0u8; 64];
+        let mut r = [0u8; 64];
+        let mut should_be = [0u8; 64];
+        for i in 0..64 {
+            l[i] = i as u8;
+            r[i] = 0xf0 ^ (i as u8).wrapping_mul(37);
+            should_be[i] = l[i] ^ r[i];
+        }
         assert_eq!(&xor512(l, r)[..], &should_be[..]);
+        assert_eq!(&xor512(r, l)[..], &should_be[..]);
+        assert_eq!(&xor512(l, l)[..], &[0u8; 64][..]);
+    }
+
+    #[test]
+    fn test_add_modulo512() {
+        let mut one = [0u8; 64];
+        one[0] = 1;
+        assert_eq!(&add_modulo512([0xffu8; 64], one)[..], &[0u8; 64][..]);
+
+        // Carry crosses boundary of 64-bit words
+        let mut l = [0u8; 64];
+        l[..8].copy_from_slice(&[0xffu8; 8]);
+        l[8..16].copy_from_slice(&[0xffu8; 8]);
+        let mut should_be = [0u8; 64];
+        should_be[16] = 1;
+        assert_eq!(&add_modulo512(l, one)[..], &should_be[..]);
+
+        let mut r = [0u8; 64];
+        r[7] = 0x80;
+        let mut should_be = [0u8; 64];
+        should_be[8] = 1;
+        assert_eq!(&add_modulo512(r, r)[..], &should_be[..]);
+        assert_eq!(&add_modulo512(m, [0u8; 64])[..], &m[..]);
+    }
+
+    #[test]
+    fn test_add_modulo512_words() {
+        let l = bytes_to_words(m);
+        let r = bytes_to_words([0xffu8; 64]);
+        assert_eq!(words_to_bytes(l)[..], m[..]);
+        assert_eq!(&words_to_bytes(add_modulo512_words(l, r))[..],
+                   &add_modulo512(m, [0xffu8; 64])[..]);
+        let mut one = [0u64; 8];
+        one[0] = 1;
+        assert_eq!(add_modulo512_words([u64::MAX; 8], one), [0u64; 8]);
     }
 
     #[test]
     fn test_S_xor512_initial_256() {
-        let l = [0x0 as u8; 64];
-        let r = [0x1 as u8; 64];
-        let should_be = [0xee as u8; 64];
+        let l = [0x0u8; 64];
+        let r = [0x1u8; 64];
+        let should_be = [0xeeu8; 64];
         assert_eq!(&S(xor512(l, r))[..], &should_be[..]);
     }
 
     #[test]
     fn test_S() {
-        let should_be = [0xfc as u8; 64];
+        let should_be = [0xfcu8; 64];
         assert_eq!(&S(xor512(h, N))[..], &should_be[..]);
     }
 
+    #[test]
+    fn test_S_ct() {
+        for i in 0..256 {
+            let should_be = [const_data::pi[i]; 64];
+            assert_eq!(&S_ct([i as u8; 64])[..], &should_be[..]);
+        }
+    }
+
     #[test]
     fn test_P() {
-        let should_be = [0xfc as u8; 64];
+        let should_be = [0xfcu8; 64];
         assert_eq!(&P(S(xor512(h, N)))[..], &should_be[..]);
     }
 
@@ -163,6 +343,37 @@ mod tests {
         assert_eq!(&result[..], &should_be[..]);
     }
 
+    #[test]
+    fn test_L_compact() {
+        let inputs = [m, P(S(xor512(h, N))), [0xffu8; 64], E(m, m)];
+        for a in inputs.iter() {
+            assert_eq!(&L_compact(*a)[..], &L(*a)[..]);
+        }
+    }
+
+    // Words are converted with explicit little-endian byte order, so this test is meaningful on
+    // targets of both endianness (e.g. `cross test --target powerpc64-unknown-linux-gnu`)
+    #[test]
+    fn test_L_endianness() {
+        let inputs = [m, P(S(xor512(h, N))), E(m, m)];
+        for a in inputs.iter() {
+            let mut should_be = [0u8; 64];
+            for (bytes, result) in a.chunks_exact(8).zip(should_be.chunks_exact_mut(8)) {
+                let mut word_bytes = [0u8; 8];
+                word_bytes.copy_from_slice(bytes);
+                let word = u64::from_le_bytes(word_bytes);
+                let mut temp = 0u64;
+                for (t, row) in const_data::A.iter().enumerate() {
+                    if (word >> (63 - t)) & 0x1 != 0 {
+                        temp ^= *row;
+                    }
+                }
+                result.copy_from_slice(&temp.to_le_bytes());
+            }
+            assert_eq!(&L(*a)[..], &should_be[..]);
+        }
+    }
+
     #[test]
     fn test_L_2() {
         let a = [0xea, 0xfd, 0x2c, 0xeb, 0x48, 0xea, 0xfd, 0x2c, 0x7a, 0x4e, 0xec, 0xe0, 0xb0,
@@ -179,6 +390,36 @@ mod tests {
         assert_eq!(&L(a)[..], &should_be[..]);
     }
 
+    #[test]
+    fn test_generate_A_precomp() {
+        for (row, should_be) in generate_A_precomp().iter().zip(precomp_data::A_precomp.iter()) {
+            assert_eq!(&row[..], &should_be[..]);
+        }
+    }
+
+    #[test]
+    fn test_generate_LPS_precomp() {
+        for (row, should_be) in generate_LPS_precomp().iter().zip(precomp_data::LPS_precomp.iter()) {
+            assert_eq!(&row[..], &should_be[..]);
+        }
+    }
+
+    #[test]
+    fn test_LPS() {
+        let inputs = [m, xor512(h, N), [0xffu8; 64], E(m, m)];
+        for a in inputs.iter() {
+            assert_eq!(&LPS(*a)[..], &L(P(S(*a)))[..]);
+        }
+    }
+
+    // Constants are stored in memory byte order, i.e. reversed comparing to hex in GOST, so the
+    // key schedule uses plain xor512
+    #[test]
+    fn test_C_byte_order() {
+        assert_eq!(const_data::C[0][..4], [0x07, 0x45, 0xa6, 0xf2]);
+        assert_eq!(const_data::C[0][60..], [0xda, 0x5b, 0x08, 0xb1]);
+    }
+
     #[test]
     fn test_key_schedule() {
         let should_be = [0x1e, 0xcf, 0x46, 0x0c, 0xf7, 0x8a, 0xd1, 0xf4, 0x33, 0xec, 0x7e, 0x1d,
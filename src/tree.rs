@@ -0,0 +1,88 @@
+//! Two-level tree hashing: input is split into segments, which are hashed independently (in
+//! parallel with `rayon` feature), and digests of segments are hashed again to get the root.
+//!
+//! **Note!** Result is **not** a Streebog digest of input and is not defined by any standard. It
+//! depends on segment length, so both sides must agree on it.
+
+#[cfg(all(feature = "rayon", feature = "alloc"))]
+use alloc::vec::Vec;
+
+use {hash512, StreebogHasher, StreebogHasher512};
+
+/// Computes tree hash of data with segments of `segment_len` bytes (the last segment may be
+/// shorter): root is Streebog-512 of concatenated Streebog-512 digests of segments (each in
+/// big-endian byte order, as returned by `get_result`). Empty data is one empty segment. Result
+/// is in big-endian byte order too.
+///
+/// Segments are hashed in parallel with `rayon` feature, result doesn't depend on it.
+///
+/// # Panics
+///
+/// Panics if `segment_len` is 0.
+pub fn tree_hash512(data: &[u8], segment_len: usize) -> [u8; 64] {
+    assert!(segment_len > 0, "segment length must be positive");
+    if data.is_empty() {
+        return hash512(&hash512(data));
+    }
+    let mut root = StreebogHasher512::new();
+    #[cfg(all(feature = "rayon", feature = "alloc"))]
+    {
+        use rayon::prelude::*;
+        let leaves: Vec<[u8; 64]> = data.par_chunks(segment_len).map(hash512).collect();
+        for leaf in leaves.iter() {
+            root.update(leaf);
+        }
+    }
+    #[cfg(not(all(feature = "rayon", feature = "alloc")))]
+    {
+        for segment in data.chunks(segment_len) {
+            root.update(&hash512(segment));
+        }
+    }
+    root.finish();
+    root.result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7) as u8).collect()
+    }
+
+    #[test]
+    fn test_single_segment() {
+        let data = data(100);
+        assert_eq!(tree_hash512(&data, 100)[..], hash512(&hash512(&data))[..]);
+        assert_eq!(tree_hash512(&data, 1000)[..], hash512(&hash512(&data))[..]);
+        assert_eq!(tree_hash512(b"", 64)[..], hash512(&hash512(b""))[..]);
+        assert_ne!(tree_hash512(&data, 100)[..], hash512(&data)[..]);
+    }
+
+    #[test]
+    fn test_segments() {
+        let data = data(3000);
+        let leaves = [hash512(&data[..1000]), hash512(&data[1000..2000]), hash512(&data[2000..])];
+        assert_eq!(tree_hash512(&data, 1000)[..], hash512(&leaves.concat())[..]);
+
+        // Ragged tail
+        let leaves = [hash512(&data[..1024]), hash512(&data[1024..2048]), hash512(&data[2048..])];
+        assert_eq!(tree_hash512(&data, 1024)[..], hash512(&leaves.concat())[..]);
+    }
+
+    #[test]
+    fn test_determinism() {
+        let data = data(10000);
+        assert_eq!(tree_hash512(&data, 64)[..], tree_hash512(&data, 64)[..]);
+        assert_ne!(tree_hash512(&data, 64)[..], tree_hash512(&data, 128)[..]);
+        assert_ne!(tree_hash512(&data, 64)[..], tree_hash512(&data[1..], 64)[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "segment length must be positive")]
+    fn test_zero_segment_len() {
+        tree_hash512(b"data", 0);
+    }
+}